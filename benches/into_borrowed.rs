@@ -0,0 +1,30 @@
+//! Compares `into_borrowed_unchecked` with the checked `unwrap_borrowed`.
+
+#[macro_use]
+extern crate criterion;
+extern crate mucow;
+
+use criterion::{black_box, Criterion};
+use mucow::MuCow;
+
+fn into_borrowed(c: &mut Criterion) {
+    let mut buf = String::from("hello");
+
+    c.bench_function("unwrap_borrowed", |b| {
+        b.iter(|| {
+            let data = black_box(MuCow::Borrowed(buf.as_mut_str()));
+            black_box(data.unwrap_borrowed());
+        })
+    });
+
+    c.bench_function("into_borrowed_unchecked", |b| {
+        b.iter(|| {
+            let data = black_box(MuCow::Borrowed(buf.as_mut_str()));
+            // SAFETY: the data is borrowed.
+            black_box(unsafe { data.into_borrowed_unchecked() });
+        })
+    });
+}
+
+criterion_group!(benches, into_borrowed);
+criterion_main!(benches);
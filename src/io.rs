@@ -0,0 +1,279 @@
+//! Impls of `std::io` traits, and the `MuCowCursor` reader.
+
+use std::io;
+use core::{cmp, mem, str};
+use alloc::borrow::{BorrowMut, ToOwned};
+use alloc::string::String;
+use alloc::vec;
+
+use MuCow;
+
+impl<'a, W> io::Write for MuCow<'a, W>
+    where W: io::Write + ToOwned,
+          W::Owned: BorrowMut<W>
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (**self).write(buf)
+    }
+
+    fn write_vectored(&mut self, bufs: &[io::IoSlice]) -> io::Result<usize> {
+        (**self).write_vectored(bufs)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (**self).flush()
+    }
+}
+
+/// Writes into a borrowed slice in the same way as `&mut [u8]`: bytes are
+/// copied to the front of the slice, which then shrinks to the unwritten
+/// remainder, and writes are cut short once it is full. Writes to owned
+/// data are appended, growing the `Vec`.
+///
+/// Never clones the data.
+impl<'a> io::Write for MuCow<'a, [u8]> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match *self {
+            MuCow::Borrowed(ref mut slice) => {
+                let mut remaining = mem::take(slice);
+                let result = remaining.write(buf);
+                *slice = remaining;
+                result
+            }
+            MuCow::Owned(ref mut owned) => owned.write(buf),
+        }
+    }
+
+    fn write_vectored(&mut self, bufs: &[io::IoSlice]) -> io::Result<usize> {
+        match *self {
+            MuCow::Borrowed(ref mut slice) => {
+                let mut remaining = mem::take(slice);
+                let result = remaining.write_vectored(bufs);
+                *slice = remaining;
+                result
+            }
+            MuCow::Owned(ref mut owned) => owned.write_vectored(bufs),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, R> io::Read for MuCow<'a, R>
+    where R: io::Read + ToOwned,
+          R::Owned: BorrowMut<R>
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (**self).read(buf)
+    }
+}
+
+impl<'a, R> io::BufRead for MuCow<'a, R>
+    where R: io::BufRead + ToOwned,
+          R::Owned: BorrowMut<R>
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        (**self).fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        (**self).consume(amt)
+    }
+}
+
+impl<'a, S> io::Seek for MuCow<'a, S>
+    where S: io::Seek + ToOwned,
+          S::Owned: BorrowMut<S>
+{
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        (**self).seek(pos)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        (**self).stream_position()
+    }
+}
+
+/// A reader over the bytes of a `MuCow<[u8]>` with a seekable position,
+/// analogous to `io::Cursor`.
+///
+/// Reading never clones the data, whether it is borrowed or owned.
+#[derive(Debug)]
+pub struct MuCowCursor<'a> {
+    inner: MuCow<'a, [u8]>,
+    pos: u64,
+}
+
+impl<'a> MuCowCursor<'a> {
+    /// Creates a cursor positioned at the start of `inner`.
+    pub fn new(inner: MuCow<'a, [u8]>) -> MuCowCursor<'a> {
+        MuCowCursor { inner, pos: 0 }
+    }
+
+    /// Returns the current position of the cursor.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Sets the position of the cursor. The position may lie past the end
+    /// of the data, in which case reads return no bytes.
+    pub fn set_position(&mut self, pos: u64) {
+        self.pos = pos;
+    }
+
+    /// Returns a reference to the underlying data.
+    pub fn get_ref(&self) -> &MuCow<'a, [u8]> {
+        &self.inner
+    }
+
+    /// Returns the bytes from the current position to the end of the data.
+    pub fn remaining(&self) -> &[u8] {
+        let data: &[u8] = &self.inner;
+        let start = cmp::min(self.pos, data.len() as u64) as usize;
+        &data[start..]
+    }
+
+    /// Unwraps the cursor, returning the underlying data.
+    pub fn into_inner(self) -> MuCow<'a, [u8]> {
+        self.inner
+    }
+}
+
+impl<'a> MuCow<'a, [u8]> {
+    /// Converts into a reader over the data, positioned at the start.
+    ///
+    /// Never clones the data.
+    pub fn cursor(self) -> MuCowCursor<'a> {
+        MuCowCursor::new(self)
+    }
+
+    /// Reads from `reader` into the data, returning the number of bytes read.
+    ///
+    /// Borrowed data is overwritten from the start, stopping once it is full
+    /// or `reader` reaches end of file, and is never cloned. Owned data has
+    /// everything up to end of file appended to it.
+    pub fn read_from<R>(&mut self, reader: &mut R) -> io::Result<usize>
+        where R: io::Read + ?Sized
+    {
+        match *self {
+            MuCow::Borrowed(ref mut slice) => {
+                let mut filled = 0;
+                while filled < slice.len() {
+                    match reader.read(&mut slice[filled..]) {
+                        Ok(0) => break,
+                        Ok(n) => filled += n,
+                        Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+                Ok(filled)
+            }
+            MuCow::Owned(ref mut owned) => reader.read_to_end(owned),
+        }
+    }
+
+    /// Overwrites the data with exactly `self.len()` bytes from `reader`,
+    /// failing with `UnexpectedEof` if there are not enough.
+    ///
+    /// Never clones or resizes the data. The contents are unspecified if an
+    /// error is returned.
+    pub fn read_exact_from<R>(&mut self, reader: &mut R) -> io::Result<()>
+        where R: io::Read + ?Sized
+    {
+        reader.read_exact(self)
+    }
+}
+
+impl<'a> MuCow<'a, str> {
+    /// Reads from `reader` until end of file, appending to the data, and
+    /// returns the number of bytes read.
+    ///
+    /// Fails with `InvalidData`, leaving the data unchanged, if the input is
+    /// not valid UTF-8. Clones the data if it is not already owned, unless
+    /// nothing is read.
+    pub fn read_from<R>(&mut self, reader: &mut R) -> io::Result<usize>
+        where R: io::Read + ?Sized
+    {
+        match *self {
+            MuCow::Borrowed(_) => {
+                let mut buf = String::new();
+                let n = reader.read_to_string(&mut buf)?;
+                if n > 0 {
+                    self.push_str(&buf);
+                }
+                Ok(n)
+            }
+            MuCow::Owned(ref mut owned) => reader.read_to_string(owned),
+        }
+    }
+
+    /// Overwrites the data with exactly `self.len()` bytes from `reader`,
+    /// failing with `UnexpectedEof` if there are not enough.
+    ///
+    /// Fails with `InvalidData` if the bytes are not valid UTF-8. The data is
+    /// left unchanged if an error is returned, and is never cloned or resized.
+    pub fn read_exact_from<R>(&mut self, reader: &mut R) -> io::Result<()>
+        where R: io::Read + ?Sized
+    {
+        let mut buf = vec![0; self.len()];
+        reader.read_exact(&mut buf)?;
+        let s = str::from_utf8(&buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        // SAFETY: the whole of the data is replaced with valid UTF-8 of the
+        // same length.
+        unsafe { (**self).as_bytes_mut() }.copy_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+impl<'a> io::Read for MuCowCursor<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.remaining().read(buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.remaining().read_exact(buf)?;
+        self.pos += buf.len() as u64;
+        Ok(())
+    }
+}
+
+impl<'a> io::BufRead for MuCowCursor<'a> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.remaining())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos += amt as u64;
+    }
+}
+
+impl<'a> io::Seek for MuCowCursor<'a> {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            io::SeekFrom::Start(n) => {
+                self.pos = n;
+                return Ok(n);
+            }
+            io::SeekFrom::End(n) => (self.inner.len() as u64, n),
+            io::SeekFrom::Current(n) => (self.pos, n),
+        };
+        match base.checked_add_signed(offset) {
+            Some(n) => {
+                self.pos = n;
+                Ok(n)
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.pos)
+    }
+}
@@ -1,20 +1,58 @@
 //! A module for working with mutably borrowed data.
+//!
+//! The crate is `no_std` compatible when the default `std` feature is
+//! disabled, provided `alloc` is available.
 
-use std::fmt;
-use std::borrow::{Borrow, BorrowMut, Cow};
-use std::ops::{Deref, DerefMut};
-use std::cmp::Ordering;
-use std::hash::{Hash, Hasher};
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+#[cfg(feature = "serde")]
+extern crate serde;
+
+use core::fmt;
+use core::ops::{Add, AddAssign, Deref, DerefMut, Index, IndexMut, Neg, Not};
+use core::ops::{BitAndAssign, BitOrAssign, BitXorAssign, DivAssign, MulAssign};
+use core::ops::{Bound, RangeBounds, RemAssign, ShlAssign, ShrAssign, SubAssign};
+use core::cmp::{self, Ordering};
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::hint;
+use core::mem;
+use core::convert::{Infallible, TryFrom};
+use core::ffi::CStr;
+use core::slice::{self, SliceIndex};
+use core::str::{self, FromStr, Utf8Error};
+use alloc::borrow::{Borrow, BorrowMut, Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use alloc::string::String;
+use alloc::vec::{self, Vec};
+#[cfg(feature = "std")]
+use std::ffi::{OsStr, OsString};
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
 
 use self::MuCow::*;
 
+#[cfg(feature = "std")]
+mod io;
+
+#[cfg(feature = "std")]
+pub use io::MuCowCursor;
+
 
 impl<'a, B: ?Sized> Borrow<B> for MuCow<'a, B>
     where B: ToOwned,
           B::Owned: 'a
 {
     fn borrow(&self) -> &B {
-        &**self
+        self
     }
 }
 
@@ -23,7 +61,7 @@ impl<'a, B: ?Sized> BorrowMut<B> for MuCow<'a, B>
           B::Owned: 'a + BorrowMut<B>
 {
     fn borrow_mut(&mut self) -> &mut B {
-        &mut **self
+        self
     }
 }
 
@@ -38,22 +76,185 @@ pub enum MuCow<'a, B: ?Sized + 'a>
     Owned(<B as ToOwned>::Owned),
 }
 
-impl<'a, B: ?Sized> Into<Cow<'a, B>> for MuCow<'a, B> where B: ToOwned {
-    fn into(self) -> Cow<'a, B> {
-        match self {
+/// Which variant a `MuCow` holds.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MuCowKind {
+    /// The data is borrowed.
+    Borrowed,
+
+    /// The data is owned.
+    Owned,
+}
+
+/// Conversion into a `MuCow`, for use in function parameters.
+///
+/// This is implemented for everything that converts into a `MuCow` via
+/// `From`, which is:
+///
+/// - `&'a mut B`, borrowing it;
+/// - `&'a mut String`, `&'a mut Vec<T>`, `&'a mut PathBuf` and
+///   `&'a mut OsString`, borrowing their contents;
+/// - `String`, `Vec<T>`, `PathBuf` and `OsString`, as owned `str`, `[T]`,
+///   `Path` and `OsStr` data;
+/// - `Cow<'a, B>`, as owned data;
+/// - `MuCow<'a, B>` itself.
+///
+/// Other owned data, such as a `u32` for a `MuCow<u32>`, does not convert,
+/// and must be wrapped in `MuCow::Owned` explicitly.
+///
+/// # Examples
+///
+/// ```
+/// use mucow::{IntoMuCow, MuCow};
+///
+/// fn shout<'a>(s: impl IntoMuCow<'a, str>) -> MuCow<'a, str> {
+///     let mut s = s.into_mucow();
+///     s.make_ascii_uppercase();
+///     s
+/// }
+///
+/// let mut buf = String::from("hello");
+/// assert!(shout(buf.as_mut_str()).is_borrowed());
+/// assert!(shout(&mut buf).is_borrowed());
+/// assert_eq!(buf, "HELLO");
+///
+/// let owned = shout(String::from("world"));
+/// assert!(owned.is_owned());
+/// assert_eq!(owned, "WORLD");
+/// ```
+pub trait IntoMuCow<'a, B: ?Sized + 'a + ToOwned> {
+    /// Converts `self` into a `MuCow`.
+    fn into_mucow(self) -> MuCow<'a, B>;
+}
+
+impl<'a, B: ?Sized + 'a + ToOwned, T> IntoMuCow<'a, B> for T where T: Into<MuCow<'a, B>> {
+    fn into_mucow(self) -> MuCow<'a, B> {
+        self.into()
+    }
+}
+
+impl<'a, B: ?Sized> From<MuCow<'a, B>> for Cow<'a, B> where B: ToOwned {
+    fn from(mucow: MuCow<'a, B>) -> Cow<'a, B> {
+        match mucow {
             Borrowed(borrowed) => Cow::Borrowed(borrowed),
             Owned(owned) => Cow::Owned(owned)
         }
     }
 }
 
+/// Owned data is moved without cloning. Borrowed data is always cloned, since
+/// a shared borrow cannot be turned into a mutable one.
+impl<'a, B: ?Sized> From<Cow<'a, B>> for MuCow<'a, B> where B: ToOwned {
+    fn from(cow: Cow<'a, B>) -> MuCow<'a, B> {
+        Owned(cow.into_owned())
+    }
+}
+
 impl<'a, B: ?Sized> Clone for MuCow<'a, B> where B: ToOwned {
     fn clone(&self) -> MuCow<'a, B> {
-        Owned((&**self).to_owned())
+        Owned((**self).to_owned())
     }
 }
 
 impl<'a, B: ?Sized> MuCow<'a, B> where B: ToOwned {
+    /// Constructs borrowed data. Usable in `const` contexts.
+    ///
+    /// ```
+    /// use mucow::MuCow;
+    ///
+    /// const fn wrap(s: &mut str) -> MuCow<'_, str> {
+    ///     MuCow::borrowed(s)
+    /// }
+    ///
+    /// let mut buf = String::from("hello");
+    /// wrap(&mut buf).make_ascii_uppercase();
+    /// assert_eq!(buf, "HELLO");
+    /// ```
+    pub const fn borrowed(borrowed: &'a mut B) -> MuCow<'a, B> {
+        Borrowed(borrowed)
+    }
+
+    /// Constructs owned data. Usable in `const` contexts.
+    ///
+    /// ```
+    /// use mucow::MuCow;
+    ///
+    /// const fn empty() -> MuCow<'static, str> {
+    ///     MuCow::owned(String::new())
+    /// }
+    ///
+    /// let mut data = empty();
+    /// assert!(data.is_owned());
+    /// data.push_str("hello");
+    /// assert_eq!(data, "hello");
+    /// ```
+    pub const fn owned(owned: <B as ToOwned>::Owned) -> MuCow<'a, B> {
+        Owned(owned)
+    }
+
+    /// Borrows `opt` if it is `Some`, and otherwise owns `default`.
+    ///
+    /// ```
+    /// use mucow::MuCow;
+    ///
+    /// let mut buf = String::from("given");
+    /// let data = MuCow::from_option(Some(buf.as_mut_str()), String::from("default"));
+    /// assert!(data.is_borrowed());
+    ///
+    /// let data = MuCow::<str>::from_option(None, String::from("default"));
+    /// assert!(data.is_owned());
+    /// assert_eq!(data, "default");
+    /// ```
+    pub fn from_option(opt: Option<&'a mut B>, default: <B as ToOwned>::Owned) -> MuCow<'a, B> {
+        match opt {
+            Some(borrowed) => Borrowed(borrowed),
+            None => Owned(default),
+        }
+    }
+
+    /// Borrows `opt` if it is `Some`, and otherwise owns the result of `f`.
+    ///
+    /// `f` is only called if `opt` is `None`.
+    ///
+    /// ```
+    /// use mucow::MuCow;
+    ///
+    /// let mut buf = String::from("given");
+    /// let data = MuCow::from_option_with(Some(buf.as_mut_str()), || unreachable!());
+    /// assert!(data.is_borrowed());
+    ///
+    /// let data = MuCow::<str>::from_option_with(None, || "default".repeat(2));
+    /// assert_eq!(data, "defaultdefault");
+    /// ```
+    pub fn from_option_with<F>(opt: Option<&'a mut B>, f: F) -> MuCow<'a, B>
+        where F: FnOnce() -> <B as ToOwned>::Owned
+    {
+        match opt {
+            Some(borrowed) => Borrowed(borrowed),
+            None => Owned(f()),
+        }
+    }
+
+    /// Borrows the data out of a mutable reference to its owned form.
+    ///
+    /// Changes to the existing contents are visible through `owned`, but
+    /// the owned container itself cannot be grown through the result.
+    pub fn from_owned_mut(owned: &'a mut <B as ToOwned>::Owned) -> MuCow<'a, B>
+        where B::Owned: BorrowMut<B>
+    {
+        Borrowed(owned.borrow_mut())
+    }
+
+    /// Mutably borrows the data out of a `Cow`.
+    ///
+    /// The `Cow` is converted to owned data first, cloning it if necessary,
+    /// so that changes made through the result are visible in the `Cow`.
+    pub fn from_cow_mut<'b>(cow: &'a mut Cow<'b, B>) -> MuCow<'a, B>
+        where B::Owned: BorrowMut<B>
+    {
+        Borrowed(cow.to_mut().borrow_mut())
+    }
+
     /// Extracts the owned data.
     ///
     /// Clones the data if it is not already owned.
@@ -63,95 +264,2570 @@ impl<'a, B: ?Sized> MuCow<'a, B> where B: ToOwned {
             Owned(owned) => owned,
         }
     }
-}
 
-impl<'a, B: ?Sized> Deref for MuCow<'a, B> where B: ToOwned {
-    type Target = B;
+    /// Applies `f` to the owned data, returning the result as owned data.
+    ///
+    /// Clones the data if it is not already owned.
+    pub fn map_owned<F>(self, f: F) -> MuCow<'a, B>
+        where F: FnOnce(<B as ToOwned>::Owned) -> <B as ToOwned>::Owned
+    {
+        Owned(f(self.into_owned()))
+    }
 
-    fn deref(&self) -> &B {
+    /// Applies the fallible `f` to the owned data, returning the result as
+    /// owned data.
+    ///
+    /// Clones the data if it is not already owned.
+    pub fn try_map_owned<F, E>(self, f: F) -> Result<MuCow<'a, B>, E>
+        where F: FnOnce(<B as ToOwned>::Owned) -> Result<<B as ToOwned>::Owned, E>
+    {
+        f(self.into_owned()).map(Owned)
+    }
+
+    /// Moves the owned data into `target`.
+    ///
+    /// If the data is borrowed, it is cloned into `target`, reusing its
+    /// existing allocation where possible.
+    pub fn into_owned_into(self, target: &mut <B as ToOwned>::Owned) {
+        match self {
+            Borrowed(borrowed) => (*borrowed).clone_into(target),
+            Owned(owned) => *target = owned,
+        }
+    }
+
+    /// Clones the data into `target`, reusing its existing allocation where
+    /// possible.
+    pub fn clone_into_owned(&self, target: &mut <B as ToOwned>::Owned) {
+        (**self).clone_into(target)
+    }
+
+    /// Extracts the borrowed data, or returns the owned data if it is not
+    /// borrowed.
+    ///
+    /// Unlike `into_owned`, this never clones the data.
+    pub fn try_into_borrowed(self) -> Result<&'a mut B, <B as ToOwned>::Owned> {
+        match self {
+            Borrowed(borrowed) => Ok(borrowed),
+            Owned(owned) => Err(owned),
+        }
+    }
+
+    /// Extracts the borrowed data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data is owned, including the owned data in the panic
+    /// message.
+    #[track_caller]
+    pub fn unwrap_borrowed(self) -> &'a mut B where B::Owned: fmt::Debug {
+        match self.try_into_borrowed() {
+            Ok(borrowed) => borrowed,
+            Err(owned) => panic!("called `MuCow::unwrap_borrowed()` on an `Owned` value: {:?}", owned),
+        }
+    }
+
+    /// Extracts the borrowed data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data is owned, with a panic message including `msg`
+    /// and the owned data.
+    #[track_caller]
+    pub fn expect_borrowed(self, msg: &str) -> &'a mut B where B::Owned: fmt::Debug {
+        match self.try_into_borrowed() {
+            Ok(borrowed) => borrowed,
+            Err(owned) => panic!("{}: {:?}", msg, owned),
+        }
+    }
+
+    /// Extracts the borrowed data without checking that it is borrowed.
+    ///
+    /// # Safety
+    ///
+    /// The data must be borrowed. Calling this on owned data is undefined
+    /// behaviour, although it panics when debug assertions are enabled.
+    #[track_caller]
+    pub unsafe fn into_borrowed_unchecked(self) -> &'a mut B {
+        match self {
+            Borrowed(borrowed) => borrowed,
+            Owned(_) => {
+                if cfg!(debug_assertions) {
+                    panic!("called `MuCow::into_borrowed_unchecked()` on an `Owned` value");
+                }
+                hint::unreachable_unchecked()
+            }
+        }
+    }
+
+    /// Extracts the owned data, or returns the borrowed data if it is not
+    /// owned.
+    ///
+    /// Unlike `into_owned`, this never clones the data.
+    pub fn try_into_owned_lossless(self) -> Result<<B as ToOwned>::Owned, &'a mut B> {
+        match self {
+            Borrowed(borrowed) => Err(borrowed),
+            Owned(owned) => Ok(owned),
+        }
+    }
+
+    /// Converts into a `MuCow` of another type, preserving the lifetime.
+    ///
+    /// `on_borrowed` is applied to borrowed data, and `on_owned` to owned
+    /// data. This can be used to narrow the data to one of its parts, in the
+    /// same way as `RefMut::map`, without cloning borrowed data.
+    pub fn map<C, F, G>(self, on_borrowed: F, on_owned: G) -> MuCow<'a, C>
+        where C: ?Sized + 'a + ToOwned,
+              F: FnOnce(&'a mut B) -> &'a mut C,
+              G: FnOnce(<B as ToOwned>::Owned) -> <C as ToOwned>::Owned
+    {
+        match self {
+            Borrowed(borrowed) => Borrowed(on_borrowed(borrowed)),
+            Owned(owned) => Owned(on_owned(owned)),
+        }
+    }
+
+    /// Converts borrowed data to owned data if `predicate` returns true.
+    ///
+    /// Owned data is returned unchanged regardless of `predicate`: there is
+    /// no way to go back to borrowed data.
+    pub fn into_owned_if<F>(self, predicate: F) -> MuCow<'a, B> where F: FnOnce(&B) -> bool {
+        match self {
+            Borrowed(borrowed) if predicate(borrowed) => Owned((*borrowed).to_owned()),
+            other => other,
+        }
+    }
+
+    /// Replaces the data with owned data produced by `transform` if
+    /// `needs_change` returns true, and otherwise returns `self` unchanged.
+    ///
+    /// This keeps data that is already in the desired form borrowed, and
+    /// avoids allocating for it.
+    pub fn map_if<F, G>(self, needs_change: F, transform: G) -> MuCow<'a, B>
+        where F: FnOnce(&B) -> bool,
+              G: FnOnce(&B) -> B::Owned
+    {
+        if needs_change(&self) {
+            Owned(transform(&self))
+        } else {
+            self
+        }
+    }
+
+    /// Converts into a value that does not borrow anything.
+    ///
+    /// Clones the data if it is not already owned.
+    pub fn into_static(self) -> MuCow<'static, B> where B: 'static {
+        Owned(self.into_owned())
+    }
+
+    /// Acquires a mutable reference to the owned form of the data.
+    ///
+    /// Clones the data if it is not already owned.
+    pub fn to_mut(&mut self) -> &mut <B as ToOwned>::Owned {
+        if let Borrowed(ref borrowed) = *self {
+            *self = Owned((**borrowed).to_owned());
+        }
         match *self {
-            Borrowed(ref borrowed) => borrowed,
-            Owned(ref owned) => owned.borrow(),
+            Borrowed(_) => unreachable!(),
+            Owned(ref mut owned) => owned,
         }
     }
-}
 
-impl<'a, B: ?Sized> DerefMut for MuCow<'a, B> where B: ToOwned, B::Owned: BorrowMut<B> {
-    fn deref_mut(&mut self) -> &mut B {
+    /// Converts the data to owned in place, and returns a mutable reference
+    /// to the owned data.
+    ///
+    /// Clones the data if it is not already owned, and does nothing
+    /// otherwise. Equivalent to `to_mut`, but the result may simply be
+    /// ignored when only the conversion is wanted.
+    pub fn make_owned(&mut self) -> &mut <B as ToOwned>::Owned {
+        self.to_mut()
+    }
+
+    /// Converts the data to owned in place using `f`, and returns a mutable
+    /// reference to the owned data.
+    ///
+    /// `f` receives the borrowed data and is only called if the data is not
+    /// already owned.
+    pub fn promote_with<F>(&mut self, f: F) -> &mut <B as ToOwned>::Owned
+        where F: FnOnce(&B) -> <B as ToOwned>::Owned
+    {
+        if let Borrowed(ref borrowed) = *self {
+            *self = Owned(f(borrowed));
+        }
         match *self {
-            Borrowed(ref mut borrowed) => borrowed,
-            Owned(ref mut owned) => owned.borrow_mut(),
+            Borrowed(_) => unreachable!(),
+            Owned(ref mut owned) => owned,
         }
     }
-}
 
-impl<'a, B: ?Sized> Eq for MuCow<'a, B> where B: Eq + ToOwned {}
+    /// Extracts the owned data, using `f` to produce it if it is not already
+    /// owned.
+    ///
+    /// `f` receives the borrowed data and is only called if the data is not
+    /// already owned.
+    pub fn into_owned_with<F>(self, f: F) -> <B as ToOwned>::Owned
+        where F: FnOnce(&B) -> <B as ToOwned>::Owned
+    {
+        match self {
+            Borrowed(borrowed) => f(borrowed),
+            Owned(owned) => owned,
+        }
+    }
 
-impl<'a, B: ?Sized> Ord for MuCow<'a, B> where B: Ord + ToOwned {
-    #[inline]
-    fn cmp(&self, other: &MuCow<'a, B>) -> Ordering {
-        Ord::cmp(&**self, &**other)
+    /// Returns which variant is held.
+    pub const fn kind(&self) -> MuCowKind {
+        match *self {
+            Borrowed(_) => MuCowKind::Borrowed,
+            Owned(_) => MuCowKind::Owned,
+        }
     }
-}
 
-impl<'a, 'b, B: ?Sized, C: ?Sized> PartialEq<MuCow<'b, C>> for MuCow<'a, B>
-    where B: PartialEq<C> + ToOwned,
-          C: ToOwned
-{
-    fn eq(&self, other: &MuCow<'b, C>) -> bool {
-        PartialEq::eq(&**self, &**other)
+    /// Returns true if the data is borrowed.
+    ///
+    /// This means no clone has occurred yet: borrowed data can still be
+    /// mutated in place.
+    pub const fn is_borrowed(&self) -> bool {
+        matches!(*self, Borrowed(_))
     }
-}
 
-impl<'a, B: ?Sized> PartialOrd for MuCow<'a, B> where B: PartialOrd + ToOwned {
-    #[inline]
-    fn partial_cmp(&self, other: &MuCow<'a, B>) -> Option<Ordering> {
-        PartialOrd::partial_cmp(&**self, &**other)
+    /// Returns true if the data is owned.
+    pub const fn is_owned(&self) -> bool {
+        matches!(*self, Owned(_))
     }
-}
 
-impl<'a, B: ?Sized> fmt::Debug for MuCow<'a, B>
-    where B: fmt::Debug + ToOwned,
-          <B as ToOwned>::Owned: fmt::Debug
-{
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    /// Returns a reference to the owned data, if the data is owned.
+    pub const fn as_owned(&self) -> Option<&<B as ToOwned>::Owned> {
         match *self {
-            Borrowed(ref b) => fmt::Debug::fmt(b, f),
-            Owned(ref o) => fmt::Debug::fmt(o, f),
+            Borrowed(_) => None,
+            Owned(ref owned) => Some(owned),
         }
     }
-}
 
-impl<'a, B: ?Sized> fmt::Display for MuCow<'a, B>
-    where B: fmt::Display + ToOwned,
-          <B as ToOwned>::Owned: fmt::Display
-{
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    /// Returns a mutable reference to the owned data, if the data is owned.
+    ///
+    /// Never clones the data.
+    pub fn as_owned_mut(&mut self) -> Option<&mut <B as ToOwned>::Owned> {
         match *self {
-            Borrowed(ref b) => fmt::Display::fmt(b, f),
-            Owned(ref o) => fmt::Display::fmt(o, f),
+            Borrowed(_) => None,
+            Owned(ref mut owned) => Some(owned),
         }
     }
-}
 
-impl<'a, B: ?Sized> Default for MuCow<'a, B>
-    where B: ToOwned,
-          <B as ToOwned>::Owned: Default
-{
-    fn default() -> MuCow<'a, B> {
-        Owned(<B as ToOwned>::Owned::default())
+    /// Returns the mutable borrow, if the data is borrowed.
+    ///
+    /// Never clones the data.
+    pub fn as_borrowed_mut(&mut self) -> Option<&mut B> {
+        match *self {
+            Borrowed(ref mut borrowed) => Some(borrowed),
+            Owned(_) => None,
+        }
     }
-}
 
-impl<'a, B: ?Sized> Hash for MuCow<'a, B> where B: Hash + ToOwned {
-    #[inline]
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        Hash::hash(&**self, state)
+    /// Extracts the owned data, leaving default owned data in its place.
+    ///
+    /// Clones the data if it is not already owned, in which case the
+    /// borrowed data is left untouched.
+    pub fn take(&mut self) -> <B as ToOwned>::Owned where B::Owned: Default {
+        self.replace(Default::default())
+    }
+
+    /// Extracts the owned data, leaving `value` in its place.
+    ///
+    /// Clones the data if it is not already owned, in which case the
+    /// borrowed data is left untouched.
+    pub fn replace(&mut self, value: <B as ToOwned>::Owned) -> <B as ToOwned>::Owned {
+        mem::replace(self, Owned(value)).into_owned()
+    }
+
+    /// Replaces the data with `value`, returning the previous owned data.
+    ///
+    /// Unlike `replace`, this never clones: if the data was borrowed, `None`
+    /// is returned rather than a copy of the borrowed data. Either way, the
+    /// data is owned afterwards.
+    pub fn replace_owned(&mut self, value: <B as ToOwned>::Owned) -> Option<<B as ToOwned>::Owned> {
+        mem::replace(self, Owned(value)).try_into_owned_lossless().ok()
+    }
+
+    /// Swaps the contents of two values without cloning either of them.
+    ///
+    /// Borrows and owned data move between the two values as-is, which is
+    /// why both must share the lifetime `'a`.
+    pub fn swap(&mut self, other: &mut MuCow<'a, B>) {
+        mem::swap(self, other)
+    }
+
+    /// Borrows the data as a `Cow`, without consuming or cloning it.
+    pub fn as_cow(&self) -> Cow<'_, B> {
+        Cow::Borrowed(self)
+    }
+
+    /// Borrows the data for a shorter lifetime, without consuming or
+    /// cloning it.
+    ///
+    /// The result is always `Borrowed`, and mutations made through it are
+    /// visible in `self`. It cannot outlive the borrow of `self`:
+    ///
+    /// ```compile_fail
+    /// use mucow::MuCow;
+    ///
+    /// let mut data: MuCow<str> = MuCow::Owned(String::from("hello"));
+    /// let short = data.reborrow();
+    /// drop(data);
+    /// assert_eq!(short, "hello");
+    /// ```
+    pub fn reborrow<'b>(&'b mut self) -> MuCow<'b, B> where B::Owned: BorrowMut<B> {
+        Borrowed(&mut **self)
     }
 }
 
-impl<'a, T: ?Sized + ToOwned> AsRef<T> for MuCow<'a, T> {
-    fn as_ref(&self) -> &T {
-        self
+impl<'a> MuCow<'a, str> {
+    /// Returns the length of the data, in bytes.
+    pub fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    /// Returns true if the data has a length of zero bytes.
+    pub fn is_empty(&self) -> bool {
+        (**self).is_empty()
+    }
+
+    /// Returns a raw pointer to the first byte of the data.
+    ///
+    /// If the data is borrowed, the pointer remains valid for as long as
+    /// the original borrow. If it is owned, the pointer remains valid until
+    /// `self` is dropped or modified by a method that may reallocate.
+    pub fn as_ptr(&self) -> *const u8 {
+        (**self).as_ptr()
+    }
+
+    /// Returns a raw mutable pointer to the first byte of the data.
+    ///
+    /// If the data is borrowed, the pointer remains valid for as long as
+    /// the original borrow. If it is owned, the pointer remains valid until
+    /// `self` is dropped or modified by a method that may reallocate.
+    ///
+    /// Writes through the pointer must keep the data valid UTF-8.
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        (**self).as_mut_ptr()
+    }
+
+    /// Extracts the data as a mutable borrow with the original lifetime.
+    ///
+    /// If the data is owned, the `String` is leaked. The memory is never
+    /// freed.
+    pub fn leak(self) -> &'a mut str {
+        match self {
+            Borrowed(borrowed) => borrowed,
+            Owned(owned) => owned.leak(),
+        }
+    }
+
+    /// Appends a string slice onto the end of the data.
+    ///
+    /// Clones the data if it is not already owned, unless `s` is empty.
+    pub fn push_str(&mut self, s: &str) {
+        if !s.is_empty() {
+            self.to_mut().push_str(s)
+        }
+    }
+
+    /// Appends a character onto the end of the data.
+    ///
+    /// Clones the data if it is not already owned.
+    pub fn push(&mut self, ch: char) {
+        self.to_mut().push(ch)
+    }
+
+    /// Inserts a character at byte index `idx`.
+    ///
+    /// Clones the data if it is not already owned. The index is checked
+    /// first, so borrowed data is not cloned if this panics.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is past the end of the data or does not lie on a char
+    /// boundary.
+    pub fn insert(&mut self, idx: usize, ch: char) {
+        assert!(self.is_char_boundary(idx), "byte index {} is not a char boundary", idx);
+        self.to_mut().insert(idx, ch)
+    }
+
+    /// Inserts a string slice at byte index `idx`.
+    ///
+    /// Clones the data if it is not already owned, unless `s` is empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is past the end of the data or does not lie on a char
+    /// boundary.
+    pub fn insert_str(&mut self, idx: usize, s: &str) {
+        assert!(self.is_char_boundary(idx), "byte index {} is not a char boundary", idx);
+        if !s.is_empty() {
+            self.to_mut().insert_str(idx, s)
+        }
+    }
+
+    /// Shortens the data to `new_len` bytes. Has no effect if `new_len` is
+    /// not less than the current length.
+    ///
+    /// Clones the data if it is not already owned, unless nothing is
+    /// removed. The boundary is checked first, so borrowed data is not
+    /// cloned if this panics.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new_len` does not lie on a char boundary.
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len() {
+            assert!(self.is_char_boundary(new_len), "byte index {} is not a char boundary", new_len);
+            self.to_mut().truncate(new_len)
+        }
+    }
+
+    /// Removes and returns the character at byte index `idx`.
+    ///
+    /// Clones the data if it is not already owned. The index is checked
+    /// first, so borrowed data is not cloned if this panics.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is not less than the length of the data or does not
+    /// lie on a char boundary.
+    pub fn remove(&mut self, idx: usize) -> char {
+        assert!(idx < self.len(), "cannot remove a char from the end of a string");
+        assert!(self.is_char_boundary(idx), "byte index {} is not a char boundary", idx);
+        self.to_mut().remove(idx)
+    }
+
+    /// Removes and returns the last character, or `None` if the data is
+    /// empty.
+    ///
+    /// Clones the data if it is not already owned, unless it is empty.
+    pub fn pop(&mut self) -> Option<char> {
+        if self.is_empty() {
+            None
+        } else {
+            self.to_mut().pop()
+        }
+    }
+
+    /// Replaces the bytes in `range` with `replace_with`.
+    ///
+    /// If `replace_with` has the same length as the range, the bytes are
+    /// overwritten in place, writing through to borrowed data. Otherwise
+    /// clones the data if it is not already owned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, or if either end does not lie
+    /// on a char boundary.
+    pub fn replace_range<R: RangeBounds<usize>>(&mut self, range: R, replace_with: &str) {
+        let start = match range.start_bound() {
+            Bound::Included(&n) => n,
+            Bound::Excluded(&n) => n.checked_add(1).expect("range start overflows usize"),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&n) => n.checked_add(1).expect("range end overflows usize"),
+            Bound::Excluded(&n) => n,
+            Bound::Unbounded => self.len(),
+        };
+        assert!(start <= end && end <= self.len(), "range {}..{} is out of bounds", start, end);
+        assert!(self.is_char_boundary(start), "byte index {} is not a char boundary", start);
+        assert!(self.is_char_boundary(end), "byte index {} is not a char boundary", end);
+        match *self {
+            Borrowed(ref mut borrowed) if end - start == replace_with.len() => {
+                // SAFETY: `start` and `end` lie on char boundaries, so
+                // replacing the bytes between them with valid UTF-8 keeps the
+                // data valid UTF-8.
+                let bytes = unsafe { borrowed.as_bytes_mut() };
+                bytes[start..end].copy_from_slice(replace_with.as_bytes());
+            }
+            _ => self.to_mut().replace_range(start..end, replace_with),
+        }
     }
+
+    /// Splits into the data before byte index `mid` and the data from `mid`
+    /// onwards, which are borrowed if the data is borrowed and owned if it
+    /// is owned.
+    ///
+    /// Never clones the data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is past the end of the data or does not lie on a char
+    /// boundary.
+    pub fn into_split_at(self, mid: usize) -> (MuCow<'a, str>, MuCow<'a, str>) {
+        match self {
+            Borrowed(borrowed) => {
+                let (left, right) = borrowed.split_at_mut(mid);
+                (Borrowed(left), Borrowed(right))
+            }
+            Owned(mut owned) => {
+                let right = owned.split_off(mid);
+                (Owned(owned), Owned(right))
+            }
+        }
+    }
+
+    /// Replaces all matches of `from` with `to`, as with `str::replace`.
+    ///
+    /// If `from` and `to` have the same length, the matches are overwritten
+    /// in place, writing through to borrowed data. Otherwise clones the data
+    /// if it is not already owned, unless there are no matches.
+    pub fn replace_str(&mut self, from: &str, to: &str) {
+        self.replacen_str(from, to, usize::MAX)
+    }
+
+    /// Replaces the first `count` matches of `from` with `to`, as with
+    /// `str::replacen`.
+    ///
+    /// If `from` and `to` have the same length, the matches are overwritten
+    /// in place, writing through to borrowed data. Otherwise clones the data
+    /// if it is not already owned, unless there are no matches.
+    pub fn replacen_str(&mut self, from: &str, to: &str, count: usize) {
+        if from == to || count == 0 {
+            return;
+        }
+        if from.len() != to.len() {
+            if self.contains(from) {
+                *self = Owned(self.replacen(from, to, count));
+            }
+            return;
+        }
+        let data: &mut str = self;
+        let mut pos = 0;
+        for _ in 0..count {
+            let start = match data[pos..].find(from) {
+                Some(i) => pos + i,
+                None => break,
+            };
+            pos = start + from.len();
+            // SAFETY: the match lies on char boundaries and is overwritten by
+            // valid UTF-8 of the same length, so the data remains valid
+            // UTF-8.
+            let bytes = unsafe { data.as_bytes_mut() };
+            bytes[start..pos].copy_from_slice(to.as_bytes());
+        }
+    }
+
+    /// Returns true if `replace_str(from, to)` would allocate, which is when
+    /// `from` and `to` differ in length and there is at least one match.
+    pub fn would_allocate(&self, from: &str, to: &str) -> bool {
+        from.len() != to.len() && self.contains(from)
+    }
+
+    /// Converts to lowercase, as with `str::to_lowercase`.
+    ///
+    /// Returns `self` unchanged, without cloning or allocating, if the data
+    /// is already lowercase.
+    pub fn to_lowercase_lazy(self) -> MuCow<'a, str> {
+        self.map_if(|s| s.chars().any(|c| !c.to_lowercase().eq(Some(c))), str::to_lowercase)
+    }
+
+    /// Removes leading and trailing whitespace, as with `str::trim`.
+    ///
+    /// Borrowed data is narrowed to the trimmed part, and owned data is
+    /// trimmed in place. Never clones or allocates.
+    pub fn trimmed(mut self) -> MuCow<'a, str> {
+        self.trim_in_place();
+        self
+    }
+
+    /// Removes `prefix` from the start of the data, returning true if it was
+    /// present. Otherwise the data is left unchanged.
+    ///
+    /// Borrowed data is narrowed, and owned data is shortened in place.
+    /// Never clones or allocates.
+    pub fn consume_prefix(&mut self, prefix: &str) -> bool {
+        if self.starts_with(prefix) {
+            let len = self.len();
+            self.narrow(prefix.len(), len);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes `suffix` from the end of the data, returning true if it was
+    /// present. Otherwise the data is left unchanged.
+    ///
+    /// Borrowed data is narrowed, and owned data is shortened in place.
+    /// Never clones or allocates.
+    pub fn consume_suffix(&mut self, suffix: &str) -> bool {
+        if self.ends_with(suffix) {
+            let len = self.len();
+            self.narrow(0, len - suffix.len());
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes leading whitespace, as with `str::trim_start`.
+    ///
+    /// Borrowed data is narrowed, and owned data is shortened in place.
+    /// Never clones or allocates.
+    pub fn trim_start_in_place(&mut self) {
+        let len = self.len();
+        let start = len - self.trim_start().len();
+        self.narrow(start, len);
+    }
+
+    /// Removes trailing whitespace, as with `str::trim_end`.
+    ///
+    /// Borrowed data is narrowed, and owned data is shortened in place.
+    /// Never clones or allocates.
+    pub fn trim_end_in_place(&mut self) {
+        let end = self.trim_end().len();
+        self.narrow(0, end);
+    }
+
+    /// Removes leading and trailing whitespace, as with `str::trim`.
+    ///
+    /// Borrowed data is narrowed, and owned data is shortened in place.
+    /// Never clones or allocates.
+    pub fn trim_in_place(&mut self) {
+        self.trim_start_in_place();
+        self.trim_end_in_place();
+    }
+
+    /// Narrows the data to the bytes from `start` to `end`, which must lie on
+    /// char boundaries.
+    fn narrow(&mut self, start: usize, end: usize) {
+        match *self {
+            Borrowed(ref mut borrowed) => *borrowed = &mut mem::take(borrowed)[start..end],
+            Owned(ref mut owned) => {
+                owned.truncate(end);
+                owned.drain(..start);
+            }
+        }
+    }
+
+    /// Converts the data to uppercase, as with `str::to_uppercase`.
+    ///
+    /// If every character's uppercase form is a single character of the
+    /// same length, such as for ASCII, the data is converted in place,
+    /// writing through to borrowed data. Otherwise the data is replaced by
+    /// a newly allocated `String`.
+    pub fn to_uppercase_in_place(&mut self) {
+        if self.is_ascii() {
+            self.make_ascii_uppercase();
+        } else {
+            self.convert_case(|c| same_len_case(c, c.to_uppercase()), str::to_uppercase);
+        }
+    }
+
+    /// Converts the data to lowercase, as with `str::to_lowercase`.
+    ///
+    /// If every character's lowercase form is a single character of the
+    /// same length, such as for ASCII, the data is converted in place,
+    /// writing through to borrowed data. Otherwise the data is replaced by
+    /// a newly allocated `String`.
+    pub fn to_lowercase_in_place(&mut self) {
+        if self.is_ascii() {
+            self.make_ascii_lowercase();
+        } else {
+            // The lowercase form of 'Σ' depends on its position in a word, so
+            // leave it to `str::to_lowercase`.
+            self.convert_case(|c| {
+                if c == 'Σ' { None } else { same_len_case(c, c.to_lowercase()) }
+            }, str::to_lowercase);
+        }
+    }
+
+    /// Replaces every character with the one given by `map`, in place, or
+    /// with the result of `convert` if `map` returns `None` for any
+    /// character.
+    fn convert_case<F>(&mut self, map: F, convert: fn(&str) -> String)
+        where F: Fn(char) -> Option<char>
+    {
+        if !self.chars().all(|c| map(c).is_some()) {
+            *self = Owned(convert(self));
+            return;
+        }
+        let data: &mut str = self;
+        let mut i = 0;
+        while let Some(c) = data[i..].chars().next() {
+            let len = c.len_utf8();
+            let mapped = map(c).unwrap();
+            // SAFETY: the character is overwritten by one with the same
+            // UTF-8 length, so the data remains valid UTF-8.
+            mapped.encode_utf8(unsafe { &mut data.as_bytes_mut()[i..i + len] });
+            i += len;
+        }
+    }
+
+    /// Converts into an iterator over the lines of the data, which are
+    /// borrowed if the data is borrowed and owned if it is owned.
+    ///
+    /// Lines are split as by `str::lines`: they end with `\n` or `\r\n`,
+    /// which is not included, and a trailing empty line is not produced.
+    /// Borrowed data is never cloned, while each owned line is copied into
+    /// its own `String`.
+    pub fn into_lines(self) -> IntoLines<'a> {
+        IntoLines { rest: StrRest::new(self) }
+    }
+
+    /// Converts into an iterator over the pieces of the data separated by
+    /// `sep`, which are borrowed if the data is borrowed and owned if it is
+    /// owned. The separators are not included.
+    ///
+    /// Borrowed data is never cloned, while each owned piece is copied into
+    /// its own `String`.
+    pub fn into_split(self, sep: char) -> IntoStrSplit<'a> {
+        IntoStrSplit { rest: StrRest::new(self), sep, finished: false }
+    }
+
+    /// Extends the data from `iter`, cloning it first if it is borrowed and
+    /// `iter` is not empty.
+    fn extend_owned<T, I>(&mut self, iter: I)
+        where I: IntoIterator<Item = T>,
+              String: Extend<T>
+    {
+        let mut iter = iter.into_iter();
+        if let Some(first) = iter.next() {
+            let additional = iter.size_hint().0 + 1;
+            let owned = self.promote_with(|borrowed| {
+                let mut owned = String::with_capacity(borrowed.len() + additional);
+                owned.push_str(borrowed);
+                owned
+            });
+            owned.extend(Some(first));
+            owned.extend(iter);
+        }
+    }
+}
+
+impl<'a, T: Clone> MuCow<'a, [T]> {
+    /// Returns the number of elements in the data.
+    pub fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    /// Returns true if the data has no elements.
+    pub fn is_empty(&self) -> bool {
+        (**self).is_empty()
+    }
+
+    /// Returns a raw pointer to the first element of the data.
+    ///
+    /// If the data is borrowed, the pointer remains valid for as long as
+    /// the original borrow. If it is owned, the pointer remains valid until
+    /// `self` is dropped or modified by a method that may reallocate.
+    pub fn as_ptr(&self) -> *const T {
+        (**self).as_ptr()
+    }
+
+    /// Returns a raw mutable pointer to the first element of the data.
+    ///
+    /// If the data is borrowed, the pointer remains valid for as long as
+    /// the original borrow. If it is owned, the pointer remains valid until
+    /// `self` is dropped or modified by a method that may reallocate.
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        (**self).as_mut_ptr()
+    }
+
+    /// Returns the element or subslice at `index`, or `None` if it is out of
+    /// bounds.
+    pub fn get<I>(&self, index: I) -> Option<&I::Output> where I: SliceIndex<[T]> {
+        (**self).get(index)
+    }
+
+    /// Returns the element or subslice at `index` mutably, or `None` if it is
+    /// out of bounds.
+    ///
+    /// Never clones the data: mutations write through to borrowed data.
+    pub fn get_mut<I>(&mut self, index: I) -> Option<&mut I::Output> where I: SliceIndex<[T]> {
+        (**self).get_mut(index)
+    }
+
+    /// Returns the element or subslice at `index`, without bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with an out-of-bounds index is undefined
+    /// behavior, even if the resulting reference is not used. For example,
+    /// this is undefined behavior:
+    ///
+    /// ```no_run
+    /// use mucow::MuCow;
+    ///
+    /// let mut values = [1, 2, 3];
+    /// let data = MuCow::Borrowed(&mut values[..]);
+    /// // Undefined behavior: index 3 is out of bounds.
+    /// let _ = unsafe { data.get_unchecked(3) };
+    /// ```
+    ///
+    /// Use `get` unless the index is already known to be in bounds.
+    pub unsafe fn get_unchecked<I>(&self, index: I) -> &I::Output where I: SliceIndex<[T]> {
+        (**self).get_unchecked(index)
+    }
+
+    /// Returns the element or subslice at `index` mutably, without bounds
+    /// checking.
+    ///
+    /// Never clones the data: mutations write through to borrowed data.
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with an out-of-bounds index is undefined
+    /// behavior, even if the resulting reference is not used.
+    pub unsafe fn get_unchecked_mut<I>(&mut self, index: I) -> &mut I::Output
+        where I: SliceIndex<[T]>
+    {
+        (**self).get_unchecked_mut(index)
+    }
+
+    /// Extracts the data as a mutable borrow with the original lifetime.
+    ///
+    /// If the data is owned, the `Vec` is leaked. The memory is never
+    /// freed.
+    pub fn leak(self) -> &'a mut [T] {
+        match self {
+            Borrowed(borrowed) => borrowed,
+            Owned(owned) => owned.leak(),
+        }
+    }
+
+    /// Extends the data from `iter`, cloning it first if it is borrowed and
+    /// `iter` is not empty.
+    fn extend_owned<U, I>(&mut self, iter: I)
+        where I: IntoIterator<Item = U>,
+              Vec<T>: Extend<U>
+    {
+        let mut iter = iter.into_iter();
+        if let Some(first) = iter.next() {
+            let additional = iter.size_hint().0 + 1;
+            let owned = self.promote_with(|borrowed| {
+                let mut owned = Vec::with_capacity(borrowed.len() + additional);
+                owned.extend_from_slice(borrowed);
+                owned
+            });
+            owned.extend(Some(first));
+            owned.extend(iter);
+        }
+    }
+
+    /// Converts the data to owned in place, keeping at most the first
+    /// `new_len` elements, and returns the owned `Vec`.
+    ///
+    /// Borrowed data is cloned into a `Vec` with capacity for `new_len`
+    /// elements.
+    fn promote_resized(&mut self, new_len: usize) -> &mut Vec<T> {
+        self.promote_with(|borrowed| {
+            let mut owned = Vec::with_capacity(new_len);
+            owned.extend_from_slice(&borrowed[..cmp::min(borrowed.len(), new_len)]);
+            owned
+        })
+    }
+
+    /// Appends an element onto the end of the data.
+    ///
+    /// Clones the data if it is not already owned.
+    pub fn push(&mut self, value: T) {
+        let len = self.len();
+        self.promote_resized(len + 1).push(value)
+    }
+
+    /// Clones and appends all elements of `other` onto the end of the data.
+    ///
+    /// Clones the data if it is not already owned, unless `other` is empty.
+    pub fn extend_from_slice(&mut self, other: &[T]) {
+        if !other.is_empty() {
+            let len = self.len();
+            self.promote_resized(len + other.len()).extend_from_slice(other)
+        }
+    }
+
+    /// Inserts an element at `index`, shifting all elements after it to the
+    /// right.
+    ///
+    /// Clones the data if it is not already owned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, value: T) {
+        let len = self.len();
+        assert!(index <= len, "insertion index (is {}) should be <= len (is {})", index, len);
+        self.promote_resized(len + 1).insert(index, value)
+    }
+
+    /// Resizes the data to `new_len` elements, filling any new elements with
+    /// clones of `value`.
+    ///
+    /// Clones the data if it is not already owned, unless the length is
+    /// unchanged. When shrinking borrowed data, only the retained elements
+    /// are cloned.
+    pub fn resize(&mut self, new_len: usize, value: T) {
+        if new_len != self.len() {
+            self.promote_resized(new_len).resize(new_len, value)
+        }
+    }
+
+    /// Resizes the data to `new_len` elements, filling any new elements
+    /// with the results of calling `f`.
+    ///
+    /// Clones the data if it is not already owned, unless the length is
+    /// unchanged. When shrinking borrowed data, only the retained elements
+    /// are cloned.
+    pub fn resize_with<F>(&mut self, new_len: usize, f: F) where F: FnMut() -> T {
+        if new_len != self.len() {
+            self.promote_resized(new_len).resize_with(new_len, f)
+        }
+    }
+
+    /// Converts into a fixed-size array, or returns `self` unchanged if the
+    /// length does not match `N`.
+    ///
+    /// Never clones the data.
+    pub fn try_into_array<const N: usize>(self) -> Result<MuCow<'a, [T; N]>, MuCow<'a, [T]>> {
+        match self {
+            Borrowed(borrowed) => {
+                if borrowed.len() == N {
+                    Ok(Borrowed(<&mut [T; N]>::try_from(borrowed).unwrap()))
+                } else {
+                    Err(Borrowed(borrowed))
+                }
+            }
+            Owned(owned) => <[T; N]>::try_from(owned).map(Owned).map_err(Owned),
+        }
+    }
+
+    /// Converts into an iterator over the elements, which are borrowed if the
+    /// data is borrowed and owned if it is owned.
+    ///
+    /// Never clones the data. Unlike `into_iter`, this lets borrowed data be
+    /// processed element by element without copying it.
+    pub fn into_items(self) -> IntoItems<'a, T> {
+        let inner = match self {
+            Borrowed(borrowed) => IntoItemsInner::Borrowed(borrowed.iter_mut()),
+            Owned(owned) => IntoItemsInner::Owned(owned.into_iter()),
+        };
+        IntoItems { inner }
+    }
+
+    /// Converts into an iterator over chunks of `chunk_size` elements, which
+    /// are borrowed if the data is borrowed and owned if it is owned. The
+    /// last chunk is shorter if the length does not divide evenly.
+    ///
+    /// Never clones the data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn into_chunks(self, chunk_size: usize) -> IntoChunks<'a, T> {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        let inner = match self {
+            Borrowed(borrowed) => IntoChunksInner::Borrowed(borrowed.chunks_mut(chunk_size)),
+            Owned(owned) => IntoChunksInner::Owned(owned.into_iter(), chunk_size),
+        };
+        IntoChunks { inner }
+    }
+
+    /// Converts into an iterator over chunks of exactly `chunk_size`
+    /// elements, which are borrowed if the data is borrowed and owned if it
+    /// is owned. Any remaining elements are skipped.
+    ///
+    /// Never clones the data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn into_chunks_exact(self, chunk_size: usize) -> IntoChunksExact<'a, T> {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        let inner = match self {
+            Borrowed(borrowed) => {
+                IntoChunksExactInner::Borrowed(borrowed.chunks_exact_mut(chunk_size))
+            }
+            Owned(owned) => IntoChunksExactInner::Owned(owned.into_iter(), chunk_size),
+        };
+        IntoChunksExact { inner }
+    }
+
+    /// Converts into an iterator over chunks of `chunk_size` elements,
+    /// starting from the end, which are borrowed if the data is borrowed and
+    /// owned if it is owned. The last chunk is shorter if the length does
+    /// not divide evenly.
+    ///
+    /// Never clones the data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn into_rchunks(self, chunk_size: usize) -> IntoRChunks<'a, T> {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        let inner = match self {
+            Borrowed(borrowed) => IntoRChunksInner::Borrowed(borrowed.rchunks_mut(chunk_size)),
+            Owned(owned) => IntoRChunksInner::Owned(owned, chunk_size),
+        };
+        IntoRChunks { inner }
+    }
+
+    /// Converts into an iterator over the pieces separated by elements
+    /// matching `pred`, which are borrowed if the data is borrowed and owned
+    /// if it is owned. The separators are not included.
+    ///
+    /// Never clones the data.
+    pub fn into_split<P>(self, pred: P) -> IntoSplit<'a, T, P> where P: FnMut(&T) -> bool {
+        let inner = match self {
+            Borrowed(borrowed) => IntoSplitInner::Borrowed(borrowed.split_mut(pred)),
+            Owned(owned) => IntoSplitInner::Owned {
+                iter: owned.into_iter(),
+                pred,
+                finished: false,
+            },
+        };
+        IntoSplit { inner }
+    }
+
+    /// Splits into the elements before `mid` and the elements from `mid`
+    /// onwards, which are borrowed if the data is borrowed and owned if it
+    /// is owned.
+    ///
+    /// Never clones the data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
+    pub fn into_split_at(self, mid: usize) -> (MuCow<'a, [T]>, MuCow<'a, [T]>) {
+        match self {
+            Borrowed(borrowed) => {
+                let (left, right) = borrowed.split_at_mut(mid);
+                (Borrowed(left), Borrowed(right))
+            }
+            Owned(mut owned) => {
+                let right = owned.split_off(mid);
+                (Owned(owned), Owned(right))
+            }
+        }
+    }
+
+    /// Splits into the first element and the rest, or returns `None` if the
+    /// data is empty. The parts are borrowed if the data is borrowed and
+    /// owned if it is owned.
+    ///
+    /// Never clones the data.
+    pub fn into_split_first(self) -> Option<(MuCow<'a, T>, MuCow<'a, [T]>)> {
+        match self {
+            Borrowed(borrowed) => {
+                borrowed.split_first_mut().map(|(first, rest)| (Borrowed(first), Borrowed(rest)))
+            }
+            Owned(mut owned) => {
+                if owned.is_empty() {
+                    None
+                } else {
+                    let first = owned.remove(0);
+                    Some((Owned(first), Owned(owned)))
+                }
+            }
+        }
+    }
+
+    /// Splits into the last element and the rest, or returns `None` if the
+    /// data is empty. The parts are borrowed if the data is borrowed and
+    /// owned if it is owned.
+    ///
+    /// Never clones the data.
+    pub fn into_split_last(self) -> Option<(MuCow<'a, T>, MuCow<'a, [T]>)> {
+        match self {
+            Borrowed(borrowed) => {
+                borrowed.split_last_mut().map(|(last, rest)| (Borrowed(last), Borrowed(rest)))
+            }
+            Owned(mut owned) => owned.pop().map(|last| (Owned(last), Owned(owned))),
+        }
+    }
+
+    /// Returns borrowed handles to the elements at several distinct indices
+    /// at once, or `None` if any index is out of bounds or repeated.
+    ///
+    /// Never clones the data. Mutations through the handles are visible in
+    /// `self`, and in the original slice if the data is borrowed.
+    ///
+    /// Each index is compared with every other, so this takes `O(N²)` time.
+    pub fn get_disjoint_mut<const N: usize>(&mut self, indices: [usize; N])
+        -> Option<[MuCow<'_, T>; N]>
+    {
+        let len = self.len();
+        for (i, &index) in indices.iter().enumerate() {
+            if index >= len || indices[..i].contains(&index) {
+                return None;
+            }
+        }
+        let ptr = self.as_mut_ptr();
+        // SAFETY: every index is in bounds and no two are equal, so each
+        // handle refers to a distinct element, and all of them borrow `self`
+        // mutably.
+        Some(indices.map(|index| Borrowed(unsafe { &mut *ptr.add(index) })))
+    }
+
+    /// Removes the first `n` elements.
+    ///
+    /// Borrowed data is narrowed, and owned data is shortened in place.
+    /// Never clones or allocates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n > len`.
+    pub fn advance(&mut self, n: usize) {
+        let len = self.len();
+        assert!(n <= len, "range end index {} out of range for slice of length {}", n, len);
+        match *self {
+            Borrowed(ref mut borrowed) => *borrowed = &mut mem::take(borrowed)[n..],
+            Owned(ref mut owned) => {
+                owned.drain(..n);
+            }
+        }
+    }
+
+    /// Removes and returns the first `n` elements, which are borrowed if the
+    /// data is borrowed and owned if it is owned.
+    ///
+    /// Never clones the data. Borrowed data does not allocate, while owned
+    /// data moves the remaining elements into a new `Vec` once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n > len`.
+    pub fn split_to(&mut self, n: usize) -> MuCow<'a, [T]> {
+        let len = self.len();
+        assert!(n <= len, "range end index {} out of range for slice of length {}", n, len);
+        match *self {
+            Borrowed(ref mut borrowed) => {
+                let (head, tail) = mem::take(borrowed).split_at_mut(n);
+                *borrowed = tail;
+                Borrowed(head)
+            }
+            Owned(ref mut owned) => {
+                let tail = owned.split_off(n);
+                Owned(mem::replace(owned, tail))
+            }
+        }
+    }
+
+    /// Returns the first element, or `None` if the data is empty.
+    pub fn first(&self) -> Option<&T> {
+        (**self).first()
+    }
+
+    /// Returns the last element, or `None` if the data is empty.
+    pub fn last(&self) -> Option<&T> {
+        (**self).last()
+    }
+
+    /// Returns the first element mutably, or `None` if the data is empty.
+    ///
+    /// Never clones the data: mutations write through to borrowed data.
+    pub fn first_mut(&mut self) -> Option<&mut T> {
+        (**self).first_mut()
+    }
+
+    /// Returns the last element mutably, or `None` if the data is empty.
+    ///
+    /// Never clones the data: mutations write through to borrowed data.
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        (**self).last_mut()
+    }
+
+    /// Returns the first `N` elements as an array, or `None` if there are
+    /// fewer than `N`.
+    pub fn first_chunk<const N: usize>(&self) -> Option<&[T; N]> {
+        (**self).first_chunk()
+    }
+
+    /// Returns an iterator over all overlapping windows of `size` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn windows(&self, size: usize) -> slice::Windows<'_, T> {
+        (**self).windows(size)
+    }
+
+    /// Returns an iterator over chunks of `chunk_size` elements. The last
+    /// chunk is shorter if the length does not divide evenly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn chunks(&self, chunk_size: usize) -> slice::Chunks<'_, T> {
+        (**self).chunks(chunk_size)
+    }
+
+    /// Returns an iterator over chunks of exactly `chunk_size` elements. Any
+    /// remaining elements are skipped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn chunks_exact(&self, chunk_size: usize) -> slice::ChunksExact<'_, T> {
+        (**self).chunks_exact(chunk_size)
+    }
+
+    /// Returns an iterator over chunks of `chunk_size` elements, starting
+    /// from the end. The last chunk is shorter if the length does not divide
+    /// evenly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn rchunks(&self, chunk_size: usize) -> slice::RChunks<'_, T> {
+        (**self).rchunks(chunk_size)
+    }
+
+    /// Returns an iterator over chunks of exactly `chunk_size` elements,
+    /// starting from the end. Any remaining elements are skipped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn rchunks_exact(&self, chunk_size: usize) -> slice::RChunksExact<'_, T> {
+        (**self).rchunks_exact(chunk_size)
+    }
+
+    /// Sorts the data, preserving the order of equal elements.
+    ///
+    /// Never clones the data: borrowed data is sorted in place, so the
+    /// original slice is reordered.
+    pub fn sort(&mut self) where T: Ord {
+        (**self).sort()
+    }
+
+    /// Sorts the data with a comparator function, preserving the order of
+    /// equal elements.
+    ///
+    /// Never clones the data: borrowed data is sorted in place, so the
+    /// original slice is reordered.
+    pub fn sort_by<F>(&mut self, compare: F) where F: FnMut(&T, &T) -> Ordering {
+        (**self).sort_by(compare)
+    }
+
+    /// Sorts the data with a key extraction function, preserving the order
+    /// of equal elements.
+    ///
+    /// Never clones the data: borrowed data is sorted in place, so the
+    /// original slice is reordered.
+    pub fn sort_by_key<K, F>(&mut self, f: F) where F: FnMut(&T) -> K, K: Ord {
+        (**self).sort_by_key(f)
+    }
+
+    /// Sorts the data, without preserving the order of equal elements.
+    ///
+    /// Never clones the data: borrowed data is sorted in place, so the
+    /// original slice is reordered.
+    pub fn sort_unstable(&mut self) where T: Ord {
+        (**self).sort_unstable()
+    }
+
+    /// Sorts the data with a comparator function, without preserving the
+    /// order of equal elements.
+    ///
+    /// Never clones the data: borrowed data is sorted in place, so the
+    /// original slice is reordered.
+    pub fn sort_unstable_by<F>(&mut self, compare: F) where F: FnMut(&T, &T) -> Ordering {
+        (**self).sort_unstable_by(compare)
+    }
+
+    /// Binary searches the sorted data for `x`, returning the index of a
+    /// match, or the index where it could be inserted to keep the data
+    /// sorted.
+    pub fn binary_search(&self, x: &T) -> Result<usize, usize> where T: Ord {
+        (**self).binary_search(x)
+    }
+
+    /// Binary searches the sorted data with a comparator function.
+    pub fn binary_search_by<F>(&self, f: F) -> Result<usize, usize>
+        where F: FnMut(&T) -> Ordering
+    {
+        (**self).binary_search_by(f)
+    }
+
+    /// Binary searches the data, sorted by a key extraction function, for
+    /// the key `b`.
+    pub fn binary_search_by_key<K, F>(&self, b: &K, f: F) -> Result<usize, usize>
+        where F: FnMut(&T) -> K,
+              K: Ord
+    {
+        (**self).binary_search_by_key(b, f)
+    }
+}
+
+impl<'a, T: Clone, const N: usize> MuCow<'a, [T; N]> {
+    /// Converts into a slice.
+    ///
+    /// Owned data is moved into a `Vec`.
+    pub fn unsize(self) -> MuCow<'a, [T]> {
+        match self {
+            Borrowed(borrowed) => Borrowed(borrowed),
+            Owned(owned) => Owned(Vec::from(owned)),
+        }
+    }
+
+    /// Splits into one `MuCow` per element, each borrowed or owned as
+    /// `self` was.
+    pub fn each_mut(self) -> [MuCow<'a, T>; N] {
+        match self {
+            Borrowed(borrowed) => borrowed.each_mut().map(Borrowed),
+            Owned(owned) => owned.map(Owned),
+        }
+    }
+}
+
+impl<'a, T: Clone> MuCow<'a, T> {
+    /// Overwrites the data with `value`.
+    ///
+    /// If the data is borrowed, the change is written through to the
+    /// borrowed location.
+    pub fn set(&mut self, value: T) {
+        match *self {
+            Borrowed(ref mut borrowed) => **borrowed = value,
+            Owned(ref mut owned) => *owned = value,
+        }
+    }
+
+    /// Overwrites the data with a clone of `other`, using `clone_from` to
+    /// reuse existing allocations.
+    ///
+    /// If the data is borrowed, the change is written through to the
+    /// borrowed location.
+    pub fn assign_from(&mut self, other: &T) {
+        match *self {
+            Borrowed(ref mut borrowed) => borrowed.clone_from(other),
+            Owned(ref mut owned) => owned.clone_from(other),
+        }
+    }
+}
+
+impl<'a, T: Clone> MuCow<'a, Vec<T>> {
+    /// Appends an element to the back of the data.
+    ///
+    /// Clones the data if it is not already owned, leaving the borrowed
+    /// vector untouched.
+    pub fn push(&mut self, value: T) {
+        self.to_mut().push(value)
+    }
+
+    /// Removes the last element from the data and returns it.
+    ///
+    /// Clones the data if it is not already owned, leaving the borrowed
+    /// vector untouched.
+    pub fn pop(&mut self) -> Option<T> {
+        self.to_mut().pop()
+    }
+
+    /// Inserts an element at position `index`, shifting all elements after
+    /// it to the right.
+    ///
+    /// Clones the data if it is not already owned, leaving the borrowed
+    /// vector untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, element: T) {
+        self.to_mut().insert(index, element)
+    }
+
+    /// Removes and returns the element at position `index`, shifting all
+    /// elements after it to the left.
+    ///
+    /// Clones the data if it is not already owned, leaving the borrowed
+    /// vector untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> T {
+        self.to_mut().remove(index)
+    }
+
+    /// Retains only the elements for which `f` returns true.
+    ///
+    /// If the data is borrowed, only the retained elements are cloned, and
+    /// the borrowed vector is left untouched.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut f: F) {
+        match *self {
+            Borrowed(ref borrowed) => {
+                let retained = borrowed.iter().filter(|x| f(x)).cloned().collect();
+                *self = Owned(retained);
+            }
+            Owned(ref mut owned) => owned.retain(f),
+        }
+    }
+
+    /// Clones and appends all elements of `other` to the data.
+    ///
+    /// Clones the data if it is not already owned, leaving the borrowed
+    /// vector untouched.
+    pub fn extend_from_slice(&mut self, other: &[T]) {
+        self.to_mut().extend_from_slice(other)
+    }
+
+    /// Moves all elements of `other` onto the end of the data, leaving
+    /// `other` empty.
+    ///
+    /// Clones the data if it is not already owned, leaving the borrowed
+    /// vector untouched.
+    pub fn append(&mut self, other: &mut Vec<T>) {
+        self.to_mut().append(other)
+    }
+
+    /// Removes the elements in `range`, returning them as an iterator.
+    ///
+    /// Clones the data if it is not already owned, leaving the borrowed
+    /// vector untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> vec::Drain<'_, T> {
+        self.to_mut().drain(range)
+    }
+
+    /// Splits the data at `at`, returning the elements from `at` onwards as
+    /// owned data.
+    ///
+    /// Clones the data if it is not already owned, leaving the borrowed
+    /// vector untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    pub fn split_off(&mut self, at: usize) -> MuCow<'a, Vec<T>> {
+        Owned(self.to_mut().split_off(at))
+    }
+}
+
+impl<'a, B: ?Sized> Deref for MuCow<'a, B> where B: ToOwned {
+    type Target = B;
+
+    fn deref(&self) -> &B {
+        match *self {
+            Borrowed(ref borrowed) => borrowed,
+            Owned(ref owned) => owned.borrow(),
+        }
+    }
+}
+
+impl<'a, B: ?Sized> DerefMut for MuCow<'a, B> where B: ToOwned, B::Owned: BorrowMut<B> {
+    fn deref_mut(&mut self) -> &mut B {
+        match *self {
+            Borrowed(ref mut borrowed) => borrowed,
+            Owned(ref mut owned) => owned.borrow_mut(),
+        }
+    }
+}
+
+/// Clones the data if it is not already owned, unless `rhs` is empty.
+impl<'a, 'b> Add<&'b str> for MuCow<'a, str> {
+    type Output = MuCow<'a, str>;
+
+    fn add(mut self, rhs: &'b str) -> MuCow<'a, str> {
+        self += rhs;
+        self
+    }
+}
+
+/// Clones the data if it is not already owned, unless `rhs` is empty.
+impl<'a, 'b> AddAssign<&'b str> for MuCow<'a, str> {
+    fn add_assign(&mut self, rhs: &'b str) {
+        self.push_str(rhs)
+    }
+}
+
+/// Clones the data if it is not already owned, unless `rhs` is empty.
+impl<'a, 'b> Add<Cow<'b, str>> for MuCow<'a, str> {
+    type Output = MuCow<'a, str>;
+
+    fn add(mut self, rhs: Cow<'b, str>) -> MuCow<'a, str> {
+        self += rhs;
+        self
+    }
+}
+
+/// Clones the data if it is not already owned, unless `rhs` is empty.
+impl<'a, 'b> AddAssign<Cow<'b, str>> for MuCow<'a, str> {
+    fn add_assign(&mut self, rhs: Cow<'b, str>) {
+        *self += &*rhs;
+    }
+}
+
+/// Clones the data if it is not already owned, unless `rhs` is empty.
+impl<'a, 'b> Add<MuCow<'b, str>> for MuCow<'a, str> {
+    type Output = MuCow<'a, str>;
+
+    fn add(mut self, rhs: MuCow<'b, str>) -> MuCow<'a, str> {
+        self += rhs;
+        self
+    }
+}
+
+/// Clones the data if it is not already owned, unless `rhs` is empty.
+impl<'a, 'b> AddAssign<MuCow<'b, str>> for MuCow<'a, str> {
+    fn add_assign(&mut self, rhs: MuCow<'b, str>) {
+        *self += &*rhs;
+    }
+}
+
+macro_rules! impl_op_assign {
+    ($($trait_:ident $method:ident),*) => {
+        $(
+            /// Writes through to borrowed data, as with `DerefMut`.
+            impl<'a, B, Rhs> $trait_<Rhs> for MuCow<'a, B>
+                where B: $trait_<Rhs> + ToOwned,
+                      B::Owned: BorrowMut<B>
+            {
+                fn $method(&mut self, rhs: Rhs) {
+                    (**self).$method(rhs)
+                }
+            }
+        )*
+    }
+}
+
+impl_op_assign! {
+    AddAssign add_assign, SubAssign sub_assign, MulAssign mul_assign,
+    DivAssign div_assign, RemAssign rem_assign, BitAndAssign bitand_assign,
+    BitOrAssign bitor_assign, BitXorAssign bitxor_assign, ShlAssign shl_assign,
+    ShrAssign shr_assign
+}
+
+impl<'a, B> Neg for MuCow<'a, B> where B: Neg + Copy {
+    type Output = B::Output;
+
+    fn neg(self) -> B::Output {
+        -*self
+    }
+}
+
+impl<'a, B> Not for MuCow<'a, B> where B: Not + Copy {
+    type Output = B::Output;
+
+    fn not(self) -> B::Output {
+        !*self
+    }
+}
+
+impl<'a, B: ?Sized, I> Index<I> for MuCow<'a, B> where B: Index<I> + ToOwned {
+    type Output = B::Output;
+
+    fn index(&self, index: I) -> &B::Output {
+        &(**self)[index]
+    }
+}
+
+impl<'a, B: ?Sized, I> IndexMut<I> for MuCow<'a, B>
+    where B: IndexMut<I> + ToOwned,
+          B::Owned: BorrowMut<B>
+{
+    fn index_mut(&mut self, index: I) -> &mut B::Output {
+        &mut (**self)[index]
+    }
+}
+
+impl<'a, B: ?Sized> Eq for MuCow<'a, B> where B: Eq + ToOwned {}
+
+impl<'a, B: ?Sized> Ord for MuCow<'a, B> where B: Ord + ToOwned {
+    #[inline]
+    fn cmp(&self, other: &MuCow<'a, B>) -> Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+impl<'a, 'b, B: ?Sized, C: ?Sized> PartialEq<MuCow<'b, C>> for MuCow<'a, B>
+    where B: PartialEq<C> + ToOwned,
+          C: ToOwned
+{
+    fn eq(&self, other: &MuCow<'b, C>) -> bool {
+        PartialEq::eq(&**self, &**other)
+    }
+}
+
+macro_rules! impl_eq {
+    ($lhs:ty, $rhs:ty) => {
+        impl<'a, 'b> PartialEq<$rhs> for $lhs {
+            #[inline]
+            fn eq(&self, other: &$rhs) -> bool {
+                PartialEq::eq(&self[..], &other[..])
+            }
+        }
+
+        impl<'a, 'b> PartialEq<$lhs> for $rhs {
+            #[inline]
+            fn eq(&self, other: &$lhs) -> bool {
+                PartialEq::eq(&self[..], &other[..])
+            }
+        }
+    }
+}
+
+impl_eq! { MuCow<'a, str>, str }
+impl_eq! { MuCow<'a, str>, &'b str }
+impl_eq! { MuCow<'a, str>, String }
+
+macro_rules! impl_ord {
+    ($lhs:ty, $rhs:ty) => {
+        impl<'a, 'b> PartialOrd<$rhs> for $lhs {
+            #[inline]
+            fn partial_cmp(&self, other: &$rhs) -> Option<Ordering> {
+                PartialOrd::partial_cmp(&self[..], &other[..])
+            }
+        }
+
+        impl<'a, 'b> PartialOrd<$lhs> for $rhs {
+            #[inline]
+            fn partial_cmp(&self, other: &$lhs) -> Option<Ordering> {
+                PartialOrd::partial_cmp(&self[..], &other[..])
+            }
+        }
+    }
+}
+
+impl_ord! { MuCow<'a, str>, str }
+impl_ord! { MuCow<'a, str>, &'b str }
+impl_ord! { MuCow<'a, str>, String }
+
+macro_rules! impl_slice_eq {
+    ([$($params:tt)*] $lhs:ty, $rhs:ty) => {
+        impl<'a, 'b, T, U, $($params)*> PartialEq<$rhs> for $lhs where T: Clone + PartialEq<U> {
+            #[inline]
+            fn eq(&self, other: &$rhs) -> bool {
+                PartialEq::eq(&self[..], &other[..])
+            }
+        }
+
+        impl<'a, 'b, T, U, $($params)*> PartialEq<$lhs> for $rhs where T: Clone, U: PartialEq<T> {
+            #[inline]
+            fn eq(&self, other: &$lhs) -> bool {
+                PartialEq::eq(&self[..], &other[..])
+            }
+        }
+    }
+}
+
+impl_slice_eq! { [] MuCow<'a, [T]>, [U] }
+impl_slice_eq! { [] MuCow<'a, [T]>, &'b [U] }
+impl_slice_eq! { [] MuCow<'a, [T]>, Vec<U> }
+impl_slice_eq! { [const N: usize] MuCow<'a, [T]>, [U; N] }
+
+macro_rules! impl_slice_ord {
+    ([$($params:tt)*] $lhs:ty, $rhs:ty) => {
+        impl<'a, 'b, T, $($params)*> PartialOrd<$rhs> for $lhs where T: Clone + PartialOrd {
+            #[inline]
+            fn partial_cmp(&self, other: &$rhs) -> Option<Ordering> {
+                PartialOrd::partial_cmp(&self[..], &other[..])
+            }
+        }
+
+        impl<'a, 'b, T, $($params)*> PartialOrd<$lhs> for $rhs where T: Clone + PartialOrd {
+            #[inline]
+            fn partial_cmp(&self, other: &$lhs) -> Option<Ordering> {
+                PartialOrd::partial_cmp(&self[..], &other[..])
+            }
+        }
+    }
+}
+
+impl_slice_ord! { [] MuCow<'a, [T]>, [T] }
+impl_slice_ord! { [] MuCow<'a, [T]>, &'b [T] }
+impl_slice_ord! { [] MuCow<'a, [T]>, Vec<T> }
+impl_slice_ord! { [const N: usize] MuCow<'a, [T]>, [T; N] }
+
+impl<'a, 'b, B: ?Sized, C: ?Sized> PartialOrd<MuCow<'b, C>> for MuCow<'a, B>
+    where B: PartialOrd<C> + ToOwned,
+          C: ToOwned
+{
+    #[inline]
+    fn partial_cmp(&self, other: &MuCow<'b, C>) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<'a, 'b, B: ?Sized, C: ?Sized> PartialEq<Cow<'b, C>> for MuCow<'a, B>
+    where B: PartialEq<C> + ToOwned,
+          C: ToOwned
+{
+    #[inline]
+    fn eq(&self, other: &Cow<'b, C>) -> bool {
+        PartialEq::eq(&**self, &**other)
+    }
+}
+
+impl<'a, 'b, B: ?Sized, C: ?Sized> PartialEq<MuCow<'b, C>> for Cow<'a, B>
+    where B: PartialEq<C> + ToOwned,
+          C: ToOwned
+{
+    #[inline]
+    fn eq(&self, other: &MuCow<'b, C>) -> bool {
+        PartialEq::eq(&**self, &**other)
+    }
+}
+
+impl<'a, 'b, B: ?Sized, C: ?Sized> PartialOrd<Cow<'b, C>> for MuCow<'a, B>
+    where B: PartialOrd<C> + ToOwned,
+          C: ToOwned
+{
+    #[inline]
+    fn partial_cmp(&self, other: &Cow<'b, C>) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<'a, 'b, B: ?Sized, C: ?Sized> PartialOrd<MuCow<'b, C>> for Cow<'a, B>
+    where B: PartialOrd<C> + ToOwned,
+          C: ToOwned
+{
+    #[inline]
+    fn partial_cmp(&self, other: &MuCow<'b, C>) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<'a, B: ?Sized> fmt::Debug for MuCow<'a, B>
+    where B: fmt::Debug + ToOwned,
+          <B as ToOwned>::Owned: fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Borrowed(ref b) => f.debug_tuple("Borrowed").field(b).finish(),
+            Owned(ref o) => f.debug_tuple("Owned").field(o).finish(),
+        }
+    }
+}
+
+impl<'a, B: ?Sized> fmt::Display for MuCow<'a, B>
+    where B: fmt::Display + ToOwned,
+          <B as ToOwned>::Owned: fmt::Display
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Borrowed(ref b) => fmt::Display::fmt(b, f),
+            Owned(ref o) => fmt::Display::fmt(o, f),
+        }
+    }
+}
+
+impl<'a, W> fmt::Write for MuCow<'a, W>
+    where W: fmt::Write + ToOwned,
+          W::Owned: BorrowMut<W>
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        (**self).write_str(s)
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        (**self).write_char(c)
+    }
+}
+
+/// Appending clones the data if it is not already owned, unless the
+/// appended string is empty.
+impl<'a> fmt::Write for MuCow<'a, str> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c);
+        Ok(())
+    }
+}
+
+macro_rules! impl_fmt {
+    ($($trait_:ident),*) => {
+        $(
+            impl<'a, B: ?Sized> fmt::$trait_ for MuCow<'a, B> where B: fmt::$trait_ + ToOwned {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    fmt::$trait_::fmt(&**self, f)
+                }
+            }
+        )*
+    }
+}
+
+impl_fmt! { LowerHex, UpperHex, Binary, Octal, LowerExp, UpperExp }
+
+/// Formats the address of the data, without any pointer metadata such as a
+/// slice length. Unlike the other formatting traits, this does not require
+/// `B` to implement `fmt::Pointer`, so `{:p}` works for unsized data such as
+/// `str` and `[T]`.
+impl<'a, B: ?Sized> fmt::Pointer for MuCow<'a, B> where B: ToOwned {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let data: *const B = &**self;
+        fmt::Pointer::fmt(&data.cast::<u8>(), f)
+    }
+}
+
+impl<'a, B: ?Sized> Default for MuCow<'a, B>
+    where B: ToOwned,
+          <B as ToOwned>::Owned: Default
+{
+    fn default() -> MuCow<'a, B> {
+        Owned(<B as ToOwned>::Owned::default())
+    }
+}
+
+impl<'a, B: ?Sized> Hash for MuCow<'a, B> where B: Hash + ToOwned {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+impl<'a, T: ?Sized + ToOwned> AsRef<T> for MuCow<'a, T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<'a> AsRef<[u8]> for MuCow<'a, str> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> AsRef<OsStr> for MuCow<'a, str> {
+    fn as_ref(&self) -> &OsStr {
+        (**self).as_ref()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> AsRef<Path> for MuCow<'a, str> {
+    fn as_ref(&self) -> &Path {
+        (**self).as_ref()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> AsRef<OsStr> for MuCow<'a, Path> {
+    fn as_ref(&self) -> &OsStr {
+        (**self).as_ref()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> AsRef<Path> for MuCow<'a, OsStr> {
+    fn as_ref(&self) -> &Path {
+        (**self).as_ref()
+    }
+}
+
+/// This is the only `AsMut` impl. In particular, `MuCow<str>` does not
+/// implement `AsMut<[u8]>`, since that would allow writing invalid UTF-8.
+impl<'a, B: ?Sized + ToOwned> AsMut<B> for MuCow<'a, B> where B::Owned: BorrowMut<B> {
+    fn as_mut(&mut self) -> &mut B {
+        self
+    }
+}
+
+impl<'a, B: ?Sized> From<&'a mut B> for MuCow<'a, B> where B: ToOwned {
+    fn from(borrowed: &'a mut B) -> MuCow<'a, B> {
+        Borrowed(borrowed)
+    }
+}
+
+impl<'a> From<String> for MuCow<'a, str> {
+    fn from(owned: String) -> MuCow<'a, str> {
+        Owned(owned)
+    }
+}
+
+impl<'a, T: Clone> From<Vec<T>> for MuCow<'a, [T]> {
+    fn from(owned: Vec<T>) -> MuCow<'a, [T]> {
+        Owned(owned)
+    }
+}
+
+/// Changes to the existing contents are visible through the `String`,
+/// but it cannot be grown.
+impl<'a> From<&'a mut String> for MuCow<'a, str> {
+    fn from(owned: &'a mut String) -> MuCow<'a, str> {
+        Borrowed(owned.as_mut_str())
+    }
+}
+
+/// Changes to the existing elements are visible through the `Vec`, but
+/// it cannot be grown.
+impl<'a, T: Clone> From<&'a mut Vec<T>> for MuCow<'a, [T]> {
+    fn from(owned: &'a mut Vec<T>) -> MuCow<'a, [T]> {
+        Borrowed(owned.as_mut_slice())
+    }
+}
+
+impl<'a> FromIterator<char> for MuCow<'a, str> {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> MuCow<'a, str> {
+        Owned(String::from_iter(iter))
+    }
+}
+
+impl<'a, 'b> FromIterator<&'b str> for MuCow<'a, str> {
+    fn from_iter<I: IntoIterator<Item = &'b str>>(iter: I) -> MuCow<'a, str> {
+        Owned(String::from_iter(iter))
+    }
+}
+
+impl<'a> FromIterator<String> for MuCow<'a, str> {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> MuCow<'a, str> {
+        Owned(String::from_iter(iter))
+    }
+}
+
+impl<'a, T: Clone> FromIterator<T> for MuCow<'a, [T]> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> MuCow<'a, [T]> {
+        Owned(Vec::from_iter(iter))
+    }
+}
+
+impl<'a, 'b, T: 'b + Clone> FromIterator<&'b T> for MuCow<'a, [T]> {
+    fn from_iter<I: IntoIterator<Item = &'b T>>(iter: I) -> MuCow<'a, [T]> {
+        Owned(iter.into_iter().cloned().collect())
+    }
+}
+
+impl<'a, T: Clone> FromIterator<T> for MuCow<'a, Vec<T>> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> MuCow<'a, Vec<T>> {
+        Owned(Vec::from_iter(iter))
+    }
+}
+
+/// Clones the data if it is not already owned, leaving the borrowed vector
+/// untouched.
+impl<'a, T: Clone> Extend<T> for MuCow<'a, Vec<T>> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.to_mut().extend(iter)
+    }
+}
+
+/// Clones the data if it is not already owned, unless the iterator is empty.
+impl<'a> Extend<char> for MuCow<'a, str> {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        self.extend_owned(iter)
+    }
+}
+
+/// Clones the data if it is not already owned, unless the iterator is empty.
+impl<'a, 'b> Extend<&'b str> for MuCow<'a, str> {
+    fn extend<I: IntoIterator<Item = &'b str>>(&mut self, iter: I) {
+        self.extend_owned(iter)
+    }
+}
+
+/// Clones the data if it is not already owned, unless the iterator is empty.
+impl<'a> Extend<String> for MuCow<'a, str> {
+    fn extend<I: IntoIterator<Item = String>>(&mut self, iter: I) {
+        self.extend_owned(iter)
+    }
+}
+
+/// Clones the data if it is not already owned, unless the iterator is empty.
+impl<'a, 'b> Extend<Cow<'b, str>> for MuCow<'a, str> {
+    fn extend<I: IntoIterator<Item = Cow<'b, str>>>(&mut self, iter: I) {
+        self.extend_owned(iter)
+    }
+}
+
+/// Clones the data if it is not already owned, unless the iterator is empty.
+impl<'a, T: Clone> Extend<T> for MuCow<'a, [T]> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.extend_owned(iter)
+    }
+}
+
+/// Clones the data if it is not already owned, unless the iterator is empty.
+impl<'a, 'b, T: 'b + Copy> Extend<&'b T> for MuCow<'a, [T]> {
+    fn extend<I: IntoIterator<Item = &'b T>>(&mut self, iter: I) {
+        self.extend_owned(iter)
+    }
+}
+
+impl<'a> FromIterator<MuCow<'a, str>> for String {
+    fn from_iter<I: IntoIterator<Item = MuCow<'a, str>>>(iter: I) -> String {
+        let mut iter = iter.into_iter();
+        match iter.next() {
+            None => String::new(),
+            Some(first) => {
+                let mut buf = first.into_owned();
+                buf.extend(iter);
+                buf
+            }
+        }
+    }
+}
+
+impl<'a> Extend<MuCow<'a, str>> for String {
+    fn extend<I: IntoIterator<Item = MuCow<'a, str>>>(&mut self, iter: I) {
+        for s in iter {
+            self.push_str(&s);
+        }
+    }
+}
+
+impl<'a, T: Clone> FromIterator<MuCow<'a, [T]>> for Vec<T> {
+    fn from_iter<I: IntoIterator<Item = MuCow<'a, [T]>>>(iter: I) -> Vec<T> {
+        let mut iter = iter.into_iter();
+        match iter.next() {
+            None => Vec::new(),
+            Some(first) => {
+                let mut buf = first.into_owned();
+                buf.extend(iter);
+                buf
+            }
+        }
+    }
+}
+
+impl<'a, T: Clone> Extend<MuCow<'a, [T]>> for Vec<T> {
+    fn extend<I: IntoIterator<Item = MuCow<'a, [T]>>>(&mut self, iter: I) {
+        for s in iter {
+            self.extend_from_slice(&s);
+        }
+    }
+}
+
+impl<'a, 'b, T: Clone> IntoIterator for &'b MuCow<'a, [T]> {
+    type Item = &'b T;
+    type IntoIter = slice::Iter<'b, T>;
+
+    fn into_iter(self) -> slice::Iter<'b, T> {
+        self.iter()
+    }
+}
+
+/// Iterates mutably over the elements, writing through to borrowed data.
+///
+/// ```
+/// use mucow::MuCow;
+///
+/// let mut values = [1, 2, 3];
+/// let mut data = MuCow::Borrowed(&mut values[..]);
+/// for x in &mut data {
+///     *x *= 10;
+/// }
+/// let mut sum = 0;
+/// for x in &data {
+///     sum += *x;
+/// }
+/// assert_eq!(sum, 60);
+/// assert!(data.is_borrowed());
+/// assert_eq!(values, [10, 20, 30]);
+/// ```
+impl<'a, 'b, T: Clone> IntoIterator for &'b mut MuCow<'a, [T]> {
+    type Item = &'b mut T;
+    type IntoIter = slice::IterMut<'b, T>;
+
+    fn into_iter(self) -> slice::IterMut<'b, T> {
+        self.iter_mut()
+    }
+}
+
+/// Clones the data if it is not already owned.
+impl<'a, T: Clone> IntoIterator for MuCow<'a, [T]> {
+    type Item = T;
+    type IntoIter = vec::IntoIter<T>;
+
+    fn into_iter(self) -> vec::IntoIter<T> {
+        self.into_owned().into_iter()
+    }
+}
+
+/// An iterator over the elements of a `MuCow<[T]>`, each borrowed or owned
+/// according to the original data.
+///
+/// Created by [`MuCow::into_items`].
+#[derive(Debug)]
+pub struct IntoItems<'a, T: 'a> {
+    inner: IntoItemsInner<'a, T>,
+}
+
+#[derive(Debug)]
+enum IntoItemsInner<'a, T: 'a> {
+    Borrowed(slice::IterMut<'a, T>),
+    Owned(vec::IntoIter<T>),
+}
+
+impl<'a, T: Clone> Iterator for IntoItems<'a, T> {
+    type Item = MuCow<'a, T>;
+
+    fn next(&mut self) -> Option<MuCow<'a, T>> {
+        match self.inner {
+            IntoItemsInner::Borrowed(ref mut iter) => iter.next().map(Borrowed),
+            IntoItemsInner::Owned(ref mut iter) => iter.next().map(Owned),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.inner {
+            IntoItemsInner::Borrowed(ref iter) => iter.size_hint(),
+            IntoItemsInner::Owned(ref iter) => iter.size_hint(),
+        }
+    }
+}
+
+impl<'a, T: Clone> DoubleEndedIterator for IntoItems<'a, T> {
+    fn next_back(&mut self) -> Option<MuCow<'a, T>> {
+        match self.inner {
+            IntoItemsInner::Borrowed(ref mut iter) => iter.next_back().map(Borrowed),
+            IntoItemsInner::Owned(ref mut iter) => iter.next_back().map(Owned),
+        }
+    }
+}
+
+impl<'a, T: Clone> ExactSizeIterator for IntoItems<'a, T> {}
+
+impl<'a, T: Clone> FusedIterator for IntoItems<'a, T> {}
+
+/// An iterator over chunks of a `MuCow<[T]>`, each borrowed or owned
+/// according to the original data.
+///
+/// Created by [`MuCow::into_chunks`].
+#[derive(Debug)]
+pub struct IntoChunks<'a, T: 'a> {
+    inner: IntoChunksInner<'a, T>,
+}
+
+#[derive(Debug)]
+enum IntoChunksInner<'a, T: 'a> {
+    Borrowed(slice::ChunksMut<'a, T>),
+    Owned(vec::IntoIter<T>, usize),
+}
+
+impl<'a, T: Clone> Iterator for IntoChunks<'a, T> {
+    type Item = MuCow<'a, [T]>;
+
+    fn next(&mut self) -> Option<MuCow<'a, [T]>> {
+        match self.inner {
+            IntoChunksInner::Borrowed(ref mut iter) => iter.next().map(Borrowed),
+            IntoChunksInner::Owned(ref mut iter, size) => {
+                if iter.len() == 0 {
+                    None
+                } else {
+                    Some(Owned(iter.by_ref().take(size).collect()))
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.inner {
+            IntoChunksInner::Borrowed(ref iter) => iter.size_hint(),
+            IntoChunksInner::Owned(ref iter, size) => {
+                let n = iter.len().div_ceil(size);
+                (n, Some(n))
+            }
+        }
+    }
+}
+
+impl<'a, T: Clone> ExactSizeIterator for IntoChunks<'a, T> {}
+
+impl<'a, T: Clone> FusedIterator for IntoChunks<'a, T> {}
+
+/// An iterator over chunks of exactly the same size of a `MuCow<[T]>`, each
+/// borrowed or owned according to the original data. Any remainder is
+/// skipped.
+///
+/// Created by [`MuCow::into_chunks_exact`].
+#[derive(Debug)]
+pub struct IntoChunksExact<'a, T: 'a> {
+    inner: IntoChunksExactInner<'a, T>,
+}
+
+#[derive(Debug)]
+enum IntoChunksExactInner<'a, T: 'a> {
+    Borrowed(slice::ChunksExactMut<'a, T>),
+    Owned(vec::IntoIter<T>, usize),
+}
+
+impl<'a, T: Clone> Iterator for IntoChunksExact<'a, T> {
+    type Item = MuCow<'a, [T]>;
+
+    fn next(&mut self) -> Option<MuCow<'a, [T]>> {
+        match self.inner {
+            IntoChunksExactInner::Borrowed(ref mut iter) => iter.next().map(Borrowed),
+            IntoChunksExactInner::Owned(ref mut iter, size) => {
+                if iter.len() < size {
+                    None
+                } else {
+                    Some(Owned(iter.by_ref().take(size).collect()))
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.inner {
+            IntoChunksExactInner::Borrowed(ref iter) => iter.size_hint(),
+            IntoChunksExactInner::Owned(ref iter, size) => {
+                let n = iter.len() / size;
+                (n, Some(n))
+            }
+        }
+    }
+}
+
+impl<'a, T: Clone> ExactSizeIterator for IntoChunksExact<'a, T> {}
+
+impl<'a, T: Clone> FusedIterator for IntoChunksExact<'a, T> {}
+
+/// An iterator over chunks of a `MuCow<[T]>`, starting from the end, each
+/// borrowed or owned according to the original data.
+///
+/// Created by [`MuCow::into_rchunks`].
+#[derive(Debug)]
+pub struct IntoRChunks<'a, T: 'a> {
+    inner: IntoRChunksInner<'a, T>,
+}
+
+#[derive(Debug)]
+enum IntoRChunksInner<'a, T: 'a> {
+    Borrowed(slice::RChunksMut<'a, T>),
+    Owned(Vec<T>, usize),
+}
+
+impl<'a, T: Clone> Iterator for IntoRChunks<'a, T> {
+    type Item = MuCow<'a, [T]>;
+
+    fn next(&mut self) -> Option<MuCow<'a, [T]>> {
+        match self.inner {
+            IntoRChunksInner::Borrowed(ref mut iter) => iter.next().map(Borrowed),
+            IntoRChunksInner::Owned(ref mut rest, size) => {
+                if rest.is_empty() {
+                    None
+                } else {
+                    let at = rest.len().saturating_sub(size);
+                    Some(Owned(rest.split_off(at)))
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.inner {
+            IntoRChunksInner::Borrowed(ref iter) => iter.size_hint(),
+            IntoRChunksInner::Owned(ref rest, size) => {
+                let n = rest.len().div_ceil(size);
+                (n, Some(n))
+            }
+        }
+    }
+}
+
+impl<'a, T: Clone> ExactSizeIterator for IntoRChunks<'a, T> {}
+
+impl<'a, T: Clone> FusedIterator for IntoRChunks<'a, T> {}
+
+/// An iterator over the pieces of a `MuCow<[T]>` separated by elements that
+/// match a predicate, each borrowed or owned according to the original data.
+///
+/// Created by [`MuCow::into_split`].
+pub struct IntoSplit<'a, T: 'a, P> where P: FnMut(&T) -> bool {
+    inner: IntoSplitInner<'a, T, P>,
+}
+
+enum IntoSplitInner<'a, T: 'a, P> where P: FnMut(&T) -> bool {
+    Borrowed(slice::SplitMut<'a, T, P>),
+    Owned {
+        iter: vec::IntoIter<T>,
+        pred: P,
+        finished: bool,
+    },
+}
+
+impl<'a, T: Clone, P> Iterator for IntoSplit<'a, T, P> where P: FnMut(&T) -> bool {
+    type Item = MuCow<'a, [T]>;
+
+    fn next(&mut self) -> Option<MuCow<'a, [T]>> {
+        match self.inner {
+            IntoSplitInner::Borrowed(ref mut iter) => iter.next().map(Borrowed),
+            IntoSplitInner::Owned { ref mut iter, ref mut pred, ref mut finished } => {
+                if *finished {
+                    return None;
+                }
+                let mut piece = Vec::new();
+                for x in iter.by_ref() {
+                    if pred(&x) {
+                        return Some(Owned(piece));
+                    }
+                    piece.push(x);
+                }
+                *finished = true;
+                Some(Owned(piece))
+            }
+        }
+    }
+}
+
+impl<'a, T: Clone, P> FusedIterator for IntoSplit<'a, T, P> where P: FnMut(&T) -> bool {}
+
+/// Returns the case mapping of `c` if it is a single character with the same
+/// UTF-8 length as `c`.
+fn same_len_case<I>(c: char, mut mapped: I) -> Option<char>
+    where I: ExactSizeIterator<Item = char>
+{
+    if mapped.len() != 1 {
+        return None;
+    }
+    mapped.next().filter(|m| m.len_utf8() == c.len_utf8())
+}
+
+/// The unconsumed part of a `MuCow<str>` being split into pieces.
+#[derive(Debug)]
+enum StrRest<'a> {
+    Borrowed(&'a mut str),
+    Owned(String, usize),
+}
+
+impl<'a> StrRest<'a> {
+    fn new(data: MuCow<'a, str>) -> StrRest<'a> {
+        match data {
+            Borrowed(borrowed) => StrRest::Borrowed(borrowed),
+            Owned(owned) => StrRest::Owned(owned, 0),
+        }
+    }
+
+    fn rest(&self) -> &str {
+        match *self {
+            StrRest::Borrowed(ref rest) => rest,
+            StrRest::Owned(ref owned, pos) => &owned[pos..],
+        }
+    }
+
+    /// Removes the first `len` bytes as a piece, then discards the next
+    /// `skip` bytes.
+    fn take_piece(&mut self, len: usize, skip: usize) -> MuCow<'a, str> {
+        match *self {
+            StrRest::Borrowed(ref mut rest) => {
+                let (piece, tail) = mem::take(rest).split_at_mut(len);
+                *rest = tail.split_at_mut(skip).1;
+                Borrowed(piece)
+            }
+            StrRest::Owned(ref owned, ref mut pos) => {
+                let piece = owned[*pos..*pos + len].into();
+                *pos += len + skip;
+                Owned(piece)
+            }
+        }
+    }
+}
+
+/// An iterator over the lines of a `MuCow<str>`, each borrowed or owned
+/// according to the original data.
+///
+/// Created by [`MuCow::into_lines`].
+#[derive(Debug)]
+pub struct IntoLines<'a> {
+    rest: StrRest<'a>,
+}
+
+impl<'a> Iterator for IntoLines<'a> {
+    type Item = MuCow<'a, str>;
+
+    fn next(&mut self) -> Option<MuCow<'a, str>> {
+        let rest = self.rest.rest();
+        if rest.is_empty() {
+            return None;
+        }
+        let (len, skip) = match rest.find('\n') {
+            Some(i) if rest[..i].ends_with('\r') => (i - 1, 2),
+            Some(i) => (i, 1),
+            None => (rest.len(), 0),
+        };
+        Some(self.rest.take_piece(len, skip))
+    }
+}
+
+impl<'a> FusedIterator for IntoLines<'a> {}
+
+/// An iterator over the pieces of a `MuCow<str>` separated by a character,
+/// each borrowed or owned according to the original data.
+///
+/// Created by [`MuCow::into_split`].
+#[derive(Debug)]
+pub struct IntoStrSplit<'a> {
+    rest: StrRest<'a>,
+    sep: char,
+    finished: bool,
+}
+
+impl<'a> Iterator for IntoStrSplit<'a> {
+    type Item = MuCow<'a, str>;
+
+    fn next(&mut self) -> Option<MuCow<'a, str>> {
+        if self.finished {
+            return None;
+        }
+        let rest = self.rest.rest();
+        let (len, skip) = match rest.find(self.sep) {
+            Some(i) => (i, self.sep.len_utf8()),
+            None => {
+                self.finished = true;
+                (rest.len(), 0)
+            }
+        };
+        Some(self.rest.take_piece(len, skip))
+    }
+}
+
+impl<'a> FusedIterator for IntoStrSplit<'a> {}
+
+/// Always parses to owned data.
+impl<'a> FromStr for MuCow<'a, str> {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<MuCow<'a, str>, Infallible> {
+        Ok(Owned(s.into()))
+    }
+}
+
+/// Always converts to owned data.
+impl<'a, 'b> TryFrom<&'b [u8]> for MuCow<'a, str> {
+    type Error = Utf8Error;
+
+    fn try_from(bytes: &'b [u8]) -> Result<MuCow<'a, str>, Utf8Error> {
+        str::from_utf8(bytes).map(|s| Owned(s.into()))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> From<PathBuf> for MuCow<'a, Path> {
+    fn from(owned: PathBuf) -> MuCow<'a, Path> {
+        Owned(owned)
+    }
+}
+
+/// Changes to the existing contents are visible through the `PathBuf`,
+/// but it cannot be grown.
+#[cfg(feature = "std")]
+impl<'a> From<&'a mut PathBuf> for MuCow<'a, Path> {
+    fn from(owned: &'a mut PathBuf) -> MuCow<'a, Path> {
+        Borrowed(&mut **owned)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> From<OsString> for MuCow<'a, OsStr> {
+    fn from(owned: OsString) -> MuCow<'a, OsStr> {
+        Owned(owned)
+    }
+}
+
+/// Changes to the existing contents are visible through the `OsString`,
+/// but it cannot be grown.
+#[cfg(feature = "std")]
+impl<'a> From<&'a mut OsString> for MuCow<'a, OsStr> {
+    fn from(owned: &'a mut OsString) -> MuCow<'a, OsStr> {
+        Borrowed(&mut **owned)
+    }
+}
+
+macro_rules! impl_from_mucow {
+    ($params:tt $b:ty => $($ptr:ident),*) => {
+        $(impl_from_mucow! { @impl $params $b, $ptr })*
+    };
+    (@impl [$($params:tt)*] $b:ty, $ptr:ident) => {
+        impl<'a, $($params)*> From<MuCow<'a, $b>> for $ptr<$b> {
+            fn from(mucow: MuCow<'a, $b>) -> $ptr<$b> {
+                match mucow {
+                    Borrowed(borrowed) => $ptr::from(&*borrowed),
+                    Owned(owned) => $ptr::from(owned),
+                }
+            }
+        }
+    }
+}
+
+impl_from_mucow! { [] str => Box, Rc, Arc }
+impl_from_mucow! { [T: Clone] [T] => Box, Rc, Arc }
+impl_from_mucow! { [] CStr => Box, Rc, Arc }
+#[cfg(feature = "std")]
+impl_from_mucow! { [] Path => Box, Rc, Arc }
+#[cfg(feature = "std")]
+impl_from_mucow! { [] OsStr => Box, Rc, Arc }
+
+#[cfg(feature = "serde")]
+impl<'a, B: ?Sized> Serialize for MuCow<'a, B> where B: Serialize + ToOwned {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+/// Always deserializes to owned data.
+#[cfg(feature = "serde")]
+impl<'a, 'de, B: ?Sized> Deserialize<'de> for MuCow<'a, B>
+    where B: ToOwned,
+          <B as ToOwned>::Owned: Deserialize<'de>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<MuCow<'a, B>, D::Error> {
+        <B as ToOwned>::Owned::deserialize(deserializer).map(Owned)
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::vec::Vec;
+}
+
+/// Constructs a `MuCow`.
+///
+/// - `mucow!(&mut expr)` borrows `expr`.
+/// - `mucow!(expr)` owns `expr`.
+/// - `mucow![a, b, c]` owns a `Vec` of two or more elements.
+///
+/// A macro cannot tell which brackets it was called with, so a single
+/// expression always means the owned data itself, with or without a
+/// trailing comma. Write a one-element list as `mucow!(vec![a])`.
+///
+/// # Examples
+///
+/// ```
+/// use mucow::{mucow, MuCow};
+///
+/// let mut buf = String::from("hello");
+/// let borrowed: MuCow<str> = mucow!(&mut buf);
+/// assert!(borrowed.is_borrowed());
+///
+/// let owned: MuCow<str> = mucow!(String::from("hello"));
+/// assert!(owned.is_owned());
+///
+/// let list: MuCow<[i32]> = mucow![1, 2, 3];
+/// assert_eq!(list, [1, 2, 3]);
+///
+/// let single: MuCow<[i32]> = mucow!(vec![1]);
+/// assert_eq!(single, [1]);
+/// ```
+///
+/// A trailing comma does not change the meaning of a single expression, so
+/// this does not build a one-element list:
+///
+/// ```compile_fail
+/// use mucow::{mucow, MuCow};
+///
+/// let list: MuCow<[i32]> = mucow![1,];
+/// ```
+///
+/// At most one trailing comma is accepted:
+///
+/// ```compile_fail
+/// use mucow::{mucow, MuCow};
+///
+/// let list: MuCow<[i32]> = mucow![1, 2,,];
+/// ```
+#[macro_export]
+macro_rules! mucow {
+    () => {
+        $crate::MuCow::Owned($crate::__private::Vec::new())
+    };
+    (&mut $e:expr) => {
+        $crate::MuCow::Borrowed(&mut $e)
+    };
+    ($e:expr $(,)?) => {
+        $crate::MuCow::Owned($e)
+    };
+    ($first:expr, $($rest:expr),+ $(,)?) => {
+        $crate::MuCow::Owned($crate::__private::Vec::from([$first, $($rest),+]))
+    };
 }
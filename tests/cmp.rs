@@ -0,0 +1,182 @@
+extern crate mucow;
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+use mucow::MuCow;
+
+#[test]
+fn str_eq_in_both_directions() {
+    let mut buf = String::from("hello");
+    let borrowed = MuCow::Borrowed(buf.as_mut_str());
+    let owned: MuCow<str> = MuCow::Owned(String::from("hello"));
+    let hello = String::from("hello");
+    let world = String::from("world");
+    for data in [&borrowed, &owned] {
+        assert!(*data == *"hello");
+        assert!(*"hello" == *data);
+        assert!(*data == "hello");
+        assert!("hello" == *data);
+        assert!(*data == hello);
+        assert!(hello == *data);
+
+        assert!(*data != *"world");
+        assert!(*"world" != *data);
+        assert!(*data != "world");
+        assert!("world" != *data);
+        assert!(*data != world);
+        assert!(world != *data);
+    }
+    assert_eq!(borrowed, owned);
+}
+
+#[test]
+fn slice_eq_in_both_directions() {
+    let mut values = [1, 2, 3];
+    let borrowed = MuCow::Borrowed(&mut values[..]);
+    let owned: MuCow<[i32]> = MuCow::Owned(vec![1, 2, 3]);
+    let same = vec![1, 2, 3];
+    let other = vec![1, 2, 4];
+    let same_ref: &[i32] = &same;
+    let other_ref: &[i32] = &other;
+    for data in [&borrowed, &owned] {
+        assert!(*data == same[..]);
+        assert!(same[..] == *data);
+        assert!(*data == same_ref);
+        assert!(same_ref == *data);
+        assert!(*data == same);
+        assert!(same == *data);
+        assert!(*data == [1, 2, 3]);
+        assert!([1, 2, 3] == *data);
+
+        assert!(*data != other[..]);
+        assert!(other[..] != *data);
+        assert!(*data != other_ref);
+        assert!(other_ref != *data);
+        assert!(*data != other);
+        assert!(other != *data);
+        assert!(*data != [1, 2]);
+        assert!([1, 2] != *data);
+    }
+    assert_eq!(borrowed, owned);
+}
+
+#[test]
+fn str_ordering() {
+    let mut buf = String::from("b");
+    let borrowed = MuCow::Borrowed(buf.as_mut_str());
+    let owned: MuCow<str> = MuCow::Owned(String::from("b"));
+    let a = String::from("a");
+    let c = String::from("c");
+    for data in [&borrowed, &owned] {
+        assert!(*data > *"a" && *data < *"c");
+        assert!(*"a" < *data && *"c" > *data);
+        assert!(*data > "a" && *data < "c");
+        assert!("a" < *data && "c" > *data);
+        assert!(*data > a && *data < c);
+        assert!(a < *data && c > *data);
+        assert_eq!(data.partial_cmp(&"b"), Some(Ordering::Equal));
+        assert_eq!("b".partial_cmp(data), Some(Ordering::Equal));
+    }
+    assert!(*"" < borrowed);
+    assert!(borrowed < *"bb");
+    assert_eq!(borrowed.cmp(&owned), Ordering::Equal);
+}
+
+#[test]
+fn slice_eq_across_element_types() {
+    let mut names = [String::from("a"), String::from("b")];
+    let borrowed = MuCow::Borrowed(&mut names[..]);
+    let owned: MuCow<[String]> = MuCow::Owned(vec![String::from("a"), String::from("b")]);
+    for data in [&borrowed, &owned] {
+        assert!(*data == ["a", "b"]);
+        assert!(["a", "b"] == *data);
+        assert!(*data != ["a", "c"]);
+        assert!(*data != ["a"]);
+        assert!(*data == vec!["a", "b"]);
+    }
+}
+
+#[test]
+fn empty_slice_comparisons() {
+    let mut values: [i32; 0] = [];
+    let borrowed = MuCow::Borrowed(&mut values[..]);
+    let owned: MuCow<[i32]> = MuCow::Owned(Vec::new());
+    let empty: &[i32] = &[];
+    let empty_array: [i32; 0] = [];
+    for data in [&borrowed, &owned] {
+        assert!(*data == empty_array);
+        assert!(*data == empty);
+        assert!(*data == Vec::<i32>::new());
+        assert!(*data != [0]);
+        assert_eq!(data.partial_cmp(&empty), Some(Ordering::Equal));
+        assert!(*data < [0]);
+        assert!(*data < vec![0]);
+    }
+    assert_eq!(borrowed, owned);
+}
+
+fn compare_across_lifetimes<'a, 'b>(x: &MuCow<'a, str>, y: &MuCow<'b, str>)
+    -> (bool, Option<Ordering>)
+{
+    (x == y, x.partial_cmp(y))
+}
+
+#[test]
+fn compare_mucows_with_distinct_lifetimes() {
+    let mut outer = String::from("same");
+    let outer = MuCow::Borrowed(outer.as_mut_str());
+    {
+        let mut inner = String::from("same");
+        let inner = MuCow::Borrowed(inner.as_mut_str());
+        assert_eq!(compare_across_lifetimes(&outer, &inner), (true, Some(Ordering::Equal)));
+        assert_eq!(compare_across_lifetimes(&inner, &outer), (true, Some(Ordering::Equal)));
+    }
+    let owned: MuCow<'static, str> = MuCow::Owned(String::from("samf"));
+    assert!(outer < owned);
+    assert!(outer != owned);
+}
+
+#[test]
+fn compare_with_cow() {
+    let mut buf = String::from("b");
+    let borrowed = MuCow::Borrowed(buf.as_mut_str());
+    let owned: MuCow<str> = MuCow::Owned(String::from("b"));
+    let cow_borrowed: Cow<str> = Cow::Borrowed("b");
+    let cow_owned: Cow<str> = Cow::Owned(String::from("b"));
+    let cow_less: Cow<str> = Cow::Borrowed("a");
+    for data in [&borrowed, &owned] {
+        for cow in [&cow_borrowed, &cow_owned] {
+            assert!(*data == *cow);
+            assert!(*cow == *data);
+            assert_eq!(data.partial_cmp(cow), Some(Ordering::Equal));
+            assert_eq!(cow.partial_cmp(data), Some(Ordering::Equal));
+        }
+        assert!(*data != *cow_less);
+        assert!(*cow_less != *data);
+        assert!(*data > *cow_less);
+        assert!(*cow_less < *data);
+    }
+}
+
+#[test]
+fn compare_slices_with_cow() {
+    let mut values = [1, 2];
+    let borrowed = MuCow::Borrowed(&mut values[..]);
+    let owned: MuCow<[i32]> = MuCow::Owned(vec![1, 2]);
+    let cow_borrowed: Cow<[i32]> = Cow::Borrowed(&[1, 2]);
+    let cow_owned: Cow<[i32]> = Cow::Owned(vec![1, 2]);
+    let cow_less: Cow<[i32]> = Cow::Borrowed(&[1, 1]);
+    for data in [&borrowed, &owned] {
+        for cow in [&cow_borrowed, &cow_owned] {
+            assert!(*data == *cow);
+            assert!(*cow == *data);
+            assert_eq!(data.partial_cmp(cow), Some(Ordering::Equal));
+            assert_eq!(cow.partial_cmp(data), Some(Ordering::Equal));
+        }
+        assert!(*data != *cow_less);
+        assert!(*cow_less != *data);
+        assert!(*data > *cow_less);
+        assert!(*cow_less < *data);
+    }
+}
@@ -0,0 +1,60 @@
+//! Allocation counting shared by the integration tests.
+//!
+//! Including this module installs a global allocator that counts the
+//! allocations made on each thread, so that tests can check that an
+//! operation does not clone its data.
+
+#![allow(dead_code)]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAlloc;
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count() {
+    let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count();
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count();
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count();
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// Runs `f`, returning its result and the number of allocations and
+/// reallocations it made on the current thread.
+pub fn allocations<R, F: FnOnce() -> R>(f: F) -> (R, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+/// Runs `f`, panicking if it allocates on the current thread.
+#[track_caller]
+pub fn assert_no_alloc<R, F: FnOnce() -> R>(f: F) -> R {
+    let (result, n) = allocations(f);
+    assert_eq!(n, 0, "expected no allocations");
+    result
+}
@@ -0,0 +1,389 @@
+extern crate mucow;
+
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use mucow::MuCow;
+
+mod common;
+use common::{allocations, assert_no_alloc};
+
+#[test]
+fn into_from_mut_str_borrows() {
+    let mut buf = String::from("hello");
+    {
+        let mut data: MuCow<str> = buf.as_mut_str().into();
+        assert!(data.is_borrowed());
+        data.make_ascii_uppercase();
+    }
+    assert_eq!(buf, "HELLO");
+}
+
+#[test]
+fn into_from_string_owns_without_copying() {
+    let owned = String::from("hello");
+    let ptr = owned.as_ptr();
+    let data: MuCow<str> = owned.into();
+    assert!(data.is_owned());
+    assert_eq!(data.as_ptr(), ptr);
+    assert_eq!(data, "hello");
+}
+
+fn takes_as_mut<T: AsMut<str>>(mut s: T) -> T {
+    s.as_mut().make_ascii_uppercase();
+    s
+}
+
+fn takes_as_mut_slice<T: AsMut<[i32]>>(mut s: T) -> T {
+    s.as_mut().reverse();
+    s
+}
+
+#[test]
+fn as_mut_str_writes_through() {
+    let mut buf = String::from("hello");
+    let data = takes_as_mut(MuCow::Borrowed(buf.as_mut_str()));
+    assert!(data.is_borrowed());
+    drop(data);
+    assert_eq!(buf, "HELLO");
+
+    let data = takes_as_mut(MuCow::<str>::Owned(String::from("hello")));
+    assert!(data.is_owned());
+    assert_eq!(data, "HELLO");
+}
+
+#[test]
+fn as_mut_slice_writes_through() {
+    let mut values = [1, 2, 3];
+    let data = takes_as_mut_slice(MuCow::Borrowed(&mut values[..]));
+    assert!(data.is_borrowed());
+    drop(data);
+    assert_eq!(values, [3, 2, 1]);
+
+    let data = takes_as_mut_slice(MuCow::<[i32]>::Owned(vec![1, 2, 3]));
+    assert!(data.is_owned());
+    assert_eq!(data, [3, 2, 1]);
+}
+
+fn shout<'a>(s: impl Into<MuCow<'a, str>>) -> MuCow<'a, str> {
+    let mut s = s.into();
+    s.make_ascii_uppercase();
+    s
+}
+
+#[test]
+fn into_mucow_parameter_accepts_each_kind() {
+    let mut buf = String::from("hello");
+    assert!(shout(buf.as_mut_str()).is_borrowed());
+    assert_eq!(buf, "HELLO");
+
+    let mut buf = String::from("hello");
+    assert!(shout(&mut buf).is_borrowed());
+    assert_eq!(buf, "HELLO");
+
+    let data = shout(String::from("hello"));
+    assert!(data.is_owned());
+    assert_eq!(data, "HELLO");
+}
+
+#[test]
+fn from_owned_mut_borrows_the_contents() {
+    let mut buf = String::from("hello");
+    {
+        let mut data = MuCow::<str>::from_owned_mut(&mut buf);
+        assert!(data.is_borrowed());
+        data.make_ascii_uppercase();
+    }
+    assert_eq!(buf, "HELLO");
+
+    let mut v = vec![1, 2, 3];
+    {
+        let mut data = MuCow::<[i32]>::from_owned_mut(&mut v);
+        assert!(data.is_borrowed());
+        data[0] = 10;
+    }
+    assert_eq!(v, [10, 2, 3]);
+}
+
+#[test]
+fn from_mut_string() {
+    let mut buf = String::from("hello");
+    {
+        let mut data: MuCow<str> = (&mut buf).into();
+        assert!(data.is_borrowed());
+        data.make_ascii_uppercase();
+    }
+    assert_eq!(buf, "HELLO");
+}
+
+#[test]
+fn from_mut_vec() {
+    let mut v = vec![3, 1, 2];
+    {
+        let mut data: MuCow<[i32]> = (&mut v).into();
+        assert!(data.is_borrowed());
+        data.sort();
+    }
+    assert_eq!(v, [1, 2, 3]);
+}
+
+#[test]
+fn from_mut_path_buf() {
+    use std::path::{Path, PathBuf};
+
+    let mut path = PathBuf::from("dir/file.txt");
+    {
+        let mut data: MuCow<Path> = (&mut path).into();
+        assert!(data.is_borrowed());
+        // `PathBuf` does not implement `BorrowMut<Path>`, so there is no
+        // `DerefMut` and the borrow has to be taken directly.
+        data.as_borrowed_mut().unwrap().as_mut_os_str().make_ascii_uppercase();
+    }
+    assert_eq!(path, Path::new("DIR/FILE.TXT"));
+}
+
+#[test]
+fn from_mut_os_string() {
+    use std::ffi::{OsStr, OsString};
+
+    let mut s = OsString::from("hello");
+    {
+        let mut data: MuCow<OsStr> = (&mut s).into();
+        assert!(data.is_borrowed());
+        data.as_borrowed_mut().unwrap().make_ascii_uppercase();
+    }
+    assert_eq!(s, "HELLO");
+}
+
+#[test]
+fn from_owned_cow_does_not_clone() {
+    let owned = String::from("hello");
+    let ptr = owned.as_ptr();
+    let cow: Cow<str> = Cow::Owned(owned);
+    let data: MuCow<str> = assert_no_alloc(|| cow.into());
+    assert!(data.is_owned());
+    assert_eq!(data.as_ptr(), ptr);
+
+    let (data, n) = allocations(|| MuCow::<str>::from(Cow::Borrowed("hello")));
+    assert_eq!(n, 1);
+    assert!(data.is_owned());
+    assert_eq!(data, "hello");
+}
+
+#[test]
+fn as_cow_borrows_without_cloning() {
+    let mut buf = String::from("hello");
+    let ptr = buf.as_ptr();
+    let data = MuCow::Borrowed(buf.as_mut_str());
+    let cow = assert_no_alloc(|| data.as_cow());
+    assert!(matches!(cow, Cow::Borrowed(_)));
+    assert_eq!(cow.as_ptr(), ptr);
+}
+
+#[test]
+fn as_cow_can_be_called_repeatedly() {
+    let mut buf = String::from("hello");
+    let borrowed = MuCow::Borrowed(buf.as_mut_str());
+    let owned: MuCow<str> = MuCow::Owned(String::from("hello"));
+    for data in [&borrowed, &owned] {
+        let first = data.as_cow();
+        let second = assert_no_alloc(|| data.as_cow());
+        assert!(matches!(first, Cow::Borrowed(_)));
+        assert!(matches!(second, Cow::Borrowed(_)));
+        assert!(first == *data);
+        assert!(second == *data);
+        assert_eq!(first.as_ptr(), data.as_ptr());
+    }
+    assert!(borrowed.is_borrowed());
+    assert!(owned.is_owned());
+
+    let mut values = [1, 2, 3];
+    let borrowed = MuCow::Borrowed(&mut values[..]);
+    let owned: MuCow<[i32]> = MuCow::Owned(vec![1, 2, 3]);
+    for data in [&borrowed, &owned] {
+        for _ in 0..3 {
+            let cow = assert_no_alloc(|| data.as_cow());
+            assert!(cow == *data);
+            assert_eq!(*cow, [1, 2, 3]);
+        }
+    }
+}
+
+#[test]
+fn from_cow_mut_writes_through_to_the_cow() {
+    let mut cow: Cow<str> = Cow::Borrowed("hello");
+    {
+        let mut data = MuCow::from_cow_mut(&mut cow);
+        assert!(data.is_borrowed());
+        data.make_ascii_uppercase();
+    }
+    assert!(matches!(cow, Cow::Owned(_)));
+    assert_eq!(cow, "HELLO");
+
+    let owned = String::from("hello");
+    let ptr = owned.as_ptr();
+    let mut cow: Cow<str> = Cow::Owned(owned);
+    {
+        let mut data = assert_no_alloc(|| MuCow::from_cow_mut(&mut cow));
+        assert_eq!(data.as_ptr(), ptr);
+        data.make_ascii_uppercase();
+    }
+    assert_eq!(cow, "HELLO");
+}
+
+#[test]
+fn into_box_moves_owned_data() {
+    let owned = String::from("hello").into_boxed_str().into_string();
+    assert_eq!(owned.capacity(), owned.len());
+    let ptr = owned.as_ptr();
+    let boxed: Box<str> = assert_no_alloc(|| MuCow::<str>::Owned(owned).into());
+    assert_eq!(boxed.as_ptr(), ptr);
+    assert_eq!(&*boxed, "hello");
+
+    let owned = vec![1, 2, 3];
+    let ptr = owned.as_ptr();
+    let boxed: Box<[i32]> = assert_no_alloc(|| MuCow::<[i32]>::Owned(owned).into());
+    assert_eq!(boxed.as_ptr(), ptr);
+    assert_eq!(&*boxed, [1, 2, 3]);
+}
+
+#[test]
+fn into_box_copies_borrowed_data() {
+    let mut buf = String::from("hello");
+    let ptr = buf.as_ptr();
+    let boxed: Box<str> = MuCow::Borrowed(buf.as_mut_str()).into();
+    assert_ne!(boxed.as_ptr(), ptr);
+    assert_eq!(&*boxed, "hello");
+
+    let mut values = [1, 2, 3];
+    let boxed: Box<[i32]> = MuCow::Borrowed(&mut values[..]).into();
+    assert_eq!(&*boxed, [1, 2, 3]);
+}
+
+#[test]
+fn into_rc_and_arc() {
+    let mut buf = String::from("hello");
+    let rc: Rc<str> = MuCow::Borrowed(buf.as_mut_str()).into();
+    assert_eq!(&*rc, "hello");
+    let rc: Rc<str> = MuCow::<str>::Owned(String::from("world")).into();
+    assert_eq!(&*rc, "world");
+
+    let mut values = [1, 2, 3];
+    let arc: Arc<[i32]> = MuCow::Borrowed(&mut values[..]).into();
+    assert_eq!(&*arc, [1, 2, 3]);
+    let arc: Arc<[i32]> = MuCow::<[i32]>::Owned(vec![4, 5]).into();
+    assert_eq!(&*arc, [4, 5]);
+}
+
+#[test]
+fn parse_to_owned_str() {
+    let data: MuCow<'static, str> = "hello".parse().unwrap();
+    assert!(data.is_owned());
+    assert_eq!(data, "hello");
+
+    let fields: Vec<MuCow<str>> = "a,b".split(',').map(|s| s.parse::<MuCow<str>>().unwrap()).collect();
+    assert_eq!(fields, ["a", "b"]);
+}
+
+#[test]
+fn try_from_bytes() {
+    use std::convert::TryFrom;
+
+    let data = MuCow::<str>::try_from(&b"hello"[..]).unwrap();
+    assert!(data.is_owned());
+    assert_eq!(data, "hello");
+
+    let err = MuCow::<str>::try_from(&b"ab\xffcd"[..]).unwrap_err();
+    assert_eq!(err.valid_up_to(), 2);
+    assert_eq!(err.error_len(), Some(1));
+
+    let err = MuCow::<str>::try_from(&b"\xc3"[..]).unwrap_err();
+    assert_eq!(err.valid_up_to(), 0);
+    assert_eq!(err.error_len(), None);
+}
+
+fn into_mucow<'a, T: mucow::IntoMuCow<'a, str>>(value: T) -> MuCow<'a, str> {
+    assert_no_alloc(|| value.into_mucow())
+}
+
+#[test]
+fn into_mucow_does_not_allocate() {
+    let mut buf = String::from("hello");
+    let ptr = buf.as_ptr();
+    assert_eq!(into_mucow(buf.as_mut_str()).as_ptr(), ptr);
+    assert_eq!(into_mucow(&mut buf).as_ptr(), ptr);
+    assert!(into_mucow(&mut buf).is_borrowed());
+
+    let data = into_mucow(buf);
+    assert!(data.is_owned());
+    assert_eq!(data.as_ptr(), ptr);
+
+    let data = into_mucow(data);
+    assert_eq!(data.as_ptr(), ptr);
+}
+
+fn fill<T: AsMut<[u8]>>(buf: &mut T, byte: u8) {
+    for b in buf.as_mut() {
+        *b = byte;
+    }
+}
+
+#[test]
+fn as_mut_bytes_on_both_variants() {
+    let mut bytes = [0u8; 3];
+    let mut data = MuCow::Borrowed(&mut bytes[..]);
+    fill(&mut data, 7);
+    assert!(data.is_borrowed());
+    drop(data);
+    assert_eq!(bytes, [7, 7, 7]);
+
+    let mut data: MuCow<[u8]> = MuCow::Owned(vec![0, 0]);
+    fill(&mut data, 9);
+    assert!(data.is_owned());
+    assert_eq!(data, [9, 9]);
+}
+
+#[test]
+fn str_as_path_and_os_str() {
+    use std::ffi::OsStr;
+    use std::fs::{self, File};
+    use std::io::Read;
+    use std::process::Command;
+
+    let path = std::env::temp_dir().join(format!("mucow-as-ref-{}.txt", std::process::id()));
+    fs::write(&path, "contents").unwrap();
+
+    let mut buf = path.to_str().unwrap().to_owned();
+    let borrowed = MuCow::Borrowed(buf.as_mut_str());
+    let owned: MuCow<str> = MuCow::Owned(path.to_str().unwrap().to_owned());
+    for data in [&borrowed, &owned] {
+        let mut contents = String::new();
+        File::open(data).unwrap().read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "contents");
+
+        let mut command = Command::new("cat");
+        command.arg(data);
+        assert_eq!(command.get_args().collect::<Vec<_>>(), [OsStr::new(&**data)]);
+    }
+    fs::remove_file(&path).unwrap();
+
+    let data: MuCow<str> = MuCow::Owned(String::from("abc"));
+    let bytes: &[u8] = data.as_ref();
+    assert_eq!(bytes, b"abc");
+}
+
+#[test]
+fn from_cow_slice() {
+    let values = [1, 2, 3];
+    let data: MuCow<[i32]> = Cow::Borrowed(&values[..]).into();
+    assert!(data.is_owned());
+    assert_ne!(data.as_ptr(), values.as_ptr());
+    assert_eq!(data, values);
+
+    let owned = vec![4, 5];
+    let ptr = owned.as_ptr();
+    let data: MuCow<[i32]> = assert_no_alloc(|| Cow::<[i32]>::Owned(owned).into());
+    assert!(data.is_owned());
+    assert_eq!(data.as_ptr(), ptr);
+}
@@ -0,0 +1,147 @@
+extern crate mucow;
+
+use std::fmt::{self, Write};
+
+use mucow::MuCow;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Flags(u8);
+
+macro_rules! forward_fmt {
+    ($($trait_:ident),*) => {
+        $(
+            impl fmt::$trait_ for Flags {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    fmt::$trait_::fmt(&self.0, f)
+                }
+            }
+        )*
+    }
+}
+
+forward_fmt! { LowerHex, UpperHex, Binary, Octal }
+
+#[test]
+fn integer_radix_formatting() {
+    let mut value = 0xbeef_u64;
+    let borrowed = MuCow::Borrowed(&mut value);
+    let owned: MuCow<u64> = MuCow::Owned(0xbeef);
+    for data in [&borrowed, &owned] {
+        assert_eq!(format!("{:x}", data), "beef");
+        assert_eq!(format!("{:X}", data), "BEEF");
+        assert_eq!(format!("{:b}", data), "1011111011101111");
+        assert_eq!(format!("{:o}", data), "137357");
+    }
+}
+
+#[test]
+fn bitflag_radix_formatting() {
+    let mut flags = Flags(0b1010_0101);
+    let borrowed = MuCow::Borrowed(&mut flags);
+    let owned: MuCow<Flags> = MuCow::Owned(Flags(0b1010_0101));
+    for data in [&borrowed, &owned] {
+        assert_eq!(format!("{:x}", data), "a5");
+        assert_eq!(format!("{:X}", data), "A5");
+        assert_eq!(format!("{:b}", data), "10100101");
+        assert_eq!(format!("{:o}", data), "245");
+    }
+}
+
+#[test]
+fn fmt_write_through_string() {
+    let mut out = String::from("> ");
+    {
+        let mut data = MuCow::Borrowed(&mut out);
+        write!(data, "{}-{}", 1, 2).unwrap();
+        data.write_char('!').unwrap();
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(out, "> 1-2!");
+
+    let mut data: MuCow<String> = MuCow::Owned(String::new());
+    write!(data, "{:>4}", 7).unwrap();
+    assert_eq!(*data, "   7");
+}
+
+#[test]
+fn fmt_write_str_matches_string() {
+    let mut buf = String::from("x=");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    let mut expected = String::from("x=");
+    for w in [&mut data as &mut dyn Write, &mut expected] {
+        write!(w, "{}", 1).unwrap();
+        w.write_char(',').unwrap();
+        write!(w, "{:03}", 2).unwrap();
+        w.write_char('é').unwrap();
+    }
+    assert!(data.is_owned());
+    assert_eq!(data, expected);
+    drop(data);
+    assert_eq!(buf, "x=");
+}
+
+#[test]
+fn fmt_write_empty_str_stays_borrowed() {
+    let mut buf = String::from("hello");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    data.write_str("").unwrap();
+    let empty = String::new();
+    write!(data, "{}", empty).unwrap();
+    assert!(data.is_borrowed());
+    data.write_char('!').unwrap();
+    assert!(data.is_owned());
+    assert_eq!(data, "hello!");
+}
+
+#[test]
+fn pointer_formats_the_data_address() {
+    let mut buf = String::from("hello");
+    let addr = format!("{:p}", buf.as_ptr());
+    let data = MuCow::Borrowed(buf.as_mut_str());
+    assert_eq!(format!("{:p}", data), addr);
+
+    let owned = vec![1, 2, 3];
+    let addr = format!("{:p}", owned.as_ptr());
+    let data: MuCow<[i32]> = MuCow::Owned(owned);
+    assert_eq!(format!("{:p}", data), addr);
+
+    let mut value = 5;
+    let addr = format!("{:p}", &value as *const i32);
+    let data = MuCow::Borrowed(&mut value);
+    assert_eq!(format!("{:p}", data), addr);
+}
+
+#[test]
+fn numeric_formatting_flags_pass_through() {
+    let mut value = 255_u32;
+    let borrowed = MuCow::Borrowed(&mut value);
+    let owned: MuCow<u32> = MuCow::Owned(255);
+    for data in [&borrowed, &owned] {
+        assert_eq!(format!("{:#x}", data), "0xff");
+        assert_eq!(format!("{:#06X}", data), "0x00FF");
+        assert_eq!(format!("{:>8b}", data), "11111111");
+        assert_eq!(format!("{:*^8o}", data), "**377***");
+        assert_eq!(format!("{:#o}", data), "0o377");
+        assert_eq!(format!("{:<6x}|", data), "ff    |");
+    }
+
+    let mut value = 1234.5_f64;
+    let data = MuCow::Borrowed(&mut value);
+    assert_eq!(format!("{:e}", data), "1.2345e3");
+    assert_eq!(format!("{:>10.2E}", data), "    1.23E3");
+    assert_eq!(format!("{:_<10.1e}", data), "1.2e3_____");
+}
+
+#[test]
+fn debug_names_the_variant() {
+    let mut buf = String::from("hello");
+    let data = MuCow::Borrowed(buf.as_mut_str());
+    assert_eq!(format!("{:?}", data), r#"Borrowed("hello")"#);
+
+    let data: MuCow<str> = MuCow::Owned(String::from("hello"));
+    assert_eq!(format!("{:?}", data), r#"Owned("hello")"#);
+
+    let data: MuCow<[i32]> = MuCow::Owned(vec![1, 2]);
+    assert_eq!(format!("{:?}", data), "Owned([1, 2])");
+    assert_eq!(format!("{:#?}", data), "Owned(\n    [\n        1,\n        2,\n    ],\n)");
+}
@@ -0,0 +1,296 @@
+extern crate mucow;
+
+use std::io::{self, BufRead, Cursor, Read, Seek, SeekFrom, Write};
+
+use mucow::{MuCow, MuCowCursor};
+
+#[test]
+fn write_to_vec() {
+    let mut out = Vec::new();
+    {
+        let mut data = MuCow::Borrowed(&mut out);
+        write!(data, "{}-{}", 1, 2).unwrap();
+        data.write_all(b"!").unwrap();
+        data.flush().unwrap();
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(out, b"1-2!");
+
+    let mut data: MuCow<Vec<u8>> = MuCow::Owned(b"> ".to_vec());
+    write!(data, "hello {}", 42).unwrap();
+    assert!(data.is_owned());
+    assert_eq!(*data, b"> hello 42");
+}
+
+#[test]
+fn write_through_generic_writer() {
+    fn emit<W: io::Write>(mut w: W) -> io::Result<W> {
+        w.write_all(b"data")?;
+        Ok(w)
+    }
+
+    let mut out = Vec::new();
+    emit(MuCow::Borrowed(&mut out)).unwrap();
+    assert_eq!(out, b"data");
+}
+
+#[test]
+fn read_from_cursor() {
+    let mut cursor = Cursor::new(b"hello world".to_vec());
+    {
+        let mut data = MuCow::Borrowed(&mut cursor);
+        let mut buf = [0; 5];
+        data.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"hello");
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(cursor.position(), 5);
+
+    let mut data: MuCow<Cursor<Vec<u8>>> = MuCow::Owned(Cursor::new(b"hello world".to_vec()));
+    let mut s = String::new();
+    data.read_to_string(&mut s).unwrap();
+    assert_eq!(s, "hello world");
+    assert_eq!(data.read(&mut [0; 4]).unwrap(), 0);
+}
+
+#[test]
+fn buf_read_lines() {
+    let mut cursor = Cursor::new(b"one\ntwo\r\nthree".to_vec());
+    let lines: Vec<String> = MuCow::Borrowed(&mut cursor).lines().map(Result::unwrap).collect();
+    assert_eq!(lines, ["one", "two", "three"]);
+    assert_eq!(cursor.position(), 14);
+
+    let data: MuCow<Cursor<Vec<u8>>> = MuCow::Owned(Cursor::new(b"a\n\nb\n".to_vec()));
+    let lines: Vec<String> = data.lines().map(Result::unwrap).collect();
+    assert_eq!(lines, ["a", "", "b"]);
+}
+
+#[test]
+fn buf_read_fill_and_consume() {
+    let mut cursor = Cursor::new(b"hello".to_vec());
+    let mut data = MuCow::Borrowed(&mut cursor);
+    assert_eq!(data.fill_buf().unwrap(), b"hello");
+    data.consume(2);
+    let mut rest = String::new();
+    data.read_line(&mut rest).unwrap();
+    assert_eq!(rest, "llo");
+}
+
+#[test]
+fn seek_then_read() {
+    let mut cursor = Cursor::new(b"0123456789".to_vec());
+    {
+        let mut data = MuCow::Borrowed(&mut cursor);
+        assert_eq!(data.seek(SeekFrom::Start(3)).unwrap(), 3);
+        let mut buf = [0; 2];
+        data.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"34");
+        assert_eq!(data.stream_position().unwrap(), 5);
+    }
+    assert_eq!(cursor.position(), 5);
+
+    let mut data: MuCow<Cursor<Vec<u8>>> = MuCow::Owned(Cursor::new(b"0123456789".to_vec()));
+    assert_eq!(data.seek(SeekFrom::End(-2)).unwrap(), 8);
+    let mut buf = [0; 2];
+    data.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"89");
+    assert_eq!(data.seek(SeekFrom::Current(-4)).unwrap(), 6);
+    data.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"67");
+}
+
+#[test]
+fn write_into_borrowed_bytes_is_bounded() {
+    let mut buf = [0u8; 4];
+    {
+        let mut data = MuCow::Borrowed(&mut buf[..]);
+        assert_eq!(data.write(b"abc").unwrap(), 3);
+        assert_eq!(data.len(), 1);
+        assert_eq!(data.write(b"de").unwrap(), 1);
+        assert_eq!(data.write(b"f").unwrap(), 0);
+        let err = data.write_all(b"g").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(&buf, b"abcd");
+}
+
+#[test]
+fn write_into_owned_bytes_grows() {
+    let mut data: MuCow<[u8]> = MuCow::Owned(b"ab".to_vec());
+    assert_eq!(data.write(b"cdef").unwrap(), 4);
+    data.write_all(&[b'g'; 100]).unwrap();
+    assert_eq!(data.len(), 106);
+    assert_eq!(data[..6], *b"abcdef");
+}
+
+#[test]
+fn write_vectored_into_bytes() {
+    let bufs = [io::IoSlice::new(b"ab"), io::IoSlice::new(b"cd")];
+
+    let mut out = [0u8; 3];
+    {
+        let mut data = MuCow::Borrowed(&mut out[..]);
+        assert_eq!(data.write_vectored(&bufs).unwrap(), 3);
+        assert!(data.is_empty());
+    }
+    assert_eq!(&out, b"abc");
+
+    let mut data: MuCow<[u8]> = MuCow::Owned(Vec::new());
+    assert_eq!(data.write_vectored(&bufs).unwrap(), 4);
+    assert_eq!(data, *b"abcd");
+}
+
+#[test]
+fn cursor_seek_past_the_end() {
+    let mut bytes = *b"hello";
+    let mut cursor = MuCow::Borrowed(&mut bytes[..]).cursor();
+    assert_eq!(cursor.seek(SeekFrom::Start(10)).unwrap(), 10);
+    assert_eq!(cursor.read(&mut [0; 4]).unwrap(), 0);
+    assert!(cursor.remaining().is_empty());
+    assert_eq!(cursor.seek(SeekFrom::End(2)).unwrap(), 7);
+    assert_eq!(cursor.seek(SeekFrom::Current(-5)).unwrap(), 2);
+    let mut buf = [0; 3];
+    cursor.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"llo");
+}
+
+#[test]
+fn cursor_negative_seek_fails() {
+    let mut cursor = MuCowCursor::new(MuCow::Owned(b"hello".to_vec()));
+    cursor.set_position(2);
+    let err = cursor.seek(SeekFrom::Current(-3)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    let err = cursor.seek(SeekFrom::End(-6)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(cursor.position(), 2);
+    assert_eq!(cursor.seek(SeekFrom::End(-5)).unwrap(), 0);
+}
+
+#[test]
+fn cursor_read_exact_failure() {
+    let mut cursor = MuCowCursor::new(MuCow::Owned(b"abc".to_vec()));
+    cursor.set_position(1);
+    let err = cursor.read_exact(&mut [0; 3]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    assert_eq!(cursor.position(), 1);
+    let mut buf = [0; 2];
+    cursor.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"bc");
+    assert_eq!(cursor.position(), 3);
+}
+
+#[test]
+fn cursor_fill_buf_and_consume() {
+    let mut bytes = *b"one\ntwo\n";
+    let mut cursor = MuCow::Borrowed(&mut bytes[..]).cursor();
+    assert_eq!(cursor.fill_buf().unwrap(), b"one\ntwo\n");
+    cursor.consume(4);
+    assert_eq!(cursor.position(), 4);
+    assert_eq!(cursor.fill_buf().unwrap(), b"two\n");
+    let mut line = String::new();
+    cursor.read_line(&mut line).unwrap();
+    assert_eq!(line, "two\n");
+    assert_eq!(cursor.fill_buf().unwrap(), b"");
+}
+
+#[test]
+fn cursor_into_inner_round_trip() {
+    let mut bytes = *b"hello";
+    let ptr = bytes.as_ptr();
+    let mut cursor = MuCow::Borrowed(&mut bytes[..]).cursor();
+    cursor.read_exact(&mut [0; 2]).unwrap();
+    let data = cursor.into_inner();
+    assert!(data.is_borrowed());
+    assert_eq!(data.as_ptr(), ptr);
+    assert_eq!(data, *b"hello");
+
+    let owned = b"world".to_vec();
+    let ptr = owned.as_ptr();
+    let cursor = MuCowCursor::new(MuCow::Owned(owned));
+    assert!(cursor.get_ref().is_owned());
+    let data = cursor.into_inner();
+    assert_eq!(data.as_ptr(), ptr);
+}
+
+/// A reader that returns at most one byte per call.
+struct Trickle<'a>(&'a [u8]);
+
+impl<'a> Read for Trickle<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = std::cmp::min(1, std::cmp::min(buf.len(), self.0.len()));
+        buf[..n].copy_from_slice(&self.0[..n]);
+        self.0 = &self.0[n..];
+        Ok(n)
+    }
+}
+
+#[test]
+fn bytes_read_from_fills_borrowed_slice() {
+    let mut buf = [0u8; 4];
+    {
+        let mut data = MuCow::Borrowed(&mut buf[..]);
+        assert_eq!(data.read_from(&mut Trickle(b"abcdef")).unwrap(), 4);
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(&buf, b"abcd");
+
+    {
+        let mut data = MuCow::Borrowed(&mut buf[..]);
+        assert_eq!(data.read_from(&mut Trickle(b"xy")).unwrap(), 2);
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(&buf, b"xycd");
+
+    let mut data: MuCow<[u8]> = MuCow::Owned(b">".to_vec());
+    assert_eq!(data.read_from(&mut Trickle(b"abc")).unwrap(), 3);
+    assert_eq!(data, *b">abc");
+}
+
+#[test]
+fn str_read_from() {
+    let mut buf = String::from("hello");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    assert_eq!(data.read_from(&mut Trickle(b"")).unwrap(), 0);
+    assert!(data.is_borrowed());
+    assert_eq!(data.read_from(&mut Trickle(", wörld".as_bytes())).unwrap(), 8);
+    assert!(data.is_owned());
+    assert_eq!(data, "hello, wörld");
+
+    let err = data.read_from(&mut Trickle(b"\xff")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(data, "hello, wörld");
+    drop(data);
+    assert_eq!(buf, "hello");
+
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    let err = data.read_from(&mut Trickle(b"ok\xff")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(data.is_borrowed());
+    assert_eq!(data, "hello");
+}
+
+#[test]
+fn str_read_exact_from() {
+    let mut buf = String::from("hello");
+    {
+        let mut data = MuCow::Borrowed(buf.as_mut_str());
+        data.read_exact_from(&mut Trickle("wörl!!".as_bytes())).unwrap();
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(buf, "wörl");
+
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    let err = data.read_exact_from(&mut Trickle(b"abc")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    assert_eq!(data, "wörl");
+
+    let err = data.read_exact_from(&mut Trickle(b"abc\xffz")).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(data.is_borrowed());
+    assert_eq!(data, "wörl");
+
+    let mut data: MuCow<str> = MuCow::Owned(String::from("ab"));
+    data.read_exact_from(&mut &b"cd"[..]).unwrap();
+    assert_eq!(data, "cd");
+}
@@ -0,0 +1,287 @@
+extern crate mucow;
+
+use mucow::MuCow;
+
+mod common;
+use common::{allocations, assert_no_alloc};
+
+#[test]
+fn collect_into_mucow_str() {
+    let data: MuCow<str> = "héllo".chars().collect();
+    assert!(data.is_owned());
+    assert_eq!(data, "héllo".chars().collect::<String>());
+
+    let data: MuCow<str> = vec!["a", "b", "c"].into_iter().collect();
+    assert_eq!(data, ["a", "b", "c"].iter().copied().collect::<String>());
+
+    let data: MuCow<str> = vec![String::from("a"), String::from("b")].into_iter().collect();
+    assert_eq!(data, "ab");
+}
+
+#[test]
+fn collect_into_mucow_slice() {
+    let data: MuCow<[i32]> = (1..4).collect();
+    assert!(data.is_owned());
+    assert_eq!(data, (1..4).collect::<Vec<_>>());
+
+    let values = [1, 2, 3];
+    let data: MuCow<[i32]> = values.iter().collect();
+    assert_eq!(data, values);
+}
+
+#[test]
+fn collect_empty_iterators() {
+    let data: MuCow<str> = std::iter::empty::<char>().collect();
+    assert!(data.is_owned());
+    assert!(data.is_empty());
+
+    let data: MuCow<str> = std::iter::empty::<&str>().collect();
+    assert!(data.is_empty());
+
+    let data: MuCow<[i32]> = std::iter::empty::<i32>().collect();
+    assert!(data.is_owned());
+    assert!(data.is_empty());
+}
+
+#[test]
+fn iterate_by_reference_mutable_reference_and_value() {
+    let mut values = [1, 2, 3];
+    {
+        let mut data = MuCow::Borrowed(&mut values[..]);
+        let mut sum = 0;
+        for x in &data {
+            sum += *x;
+        }
+        assert_eq!(sum, 6);
+        for x in &mut data {
+            *x *= 10;
+        }
+        assert!(data.is_borrowed());
+        let collected: Vec<i32> = data.into_iter().collect();
+        assert_eq!(collected, [10, 20, 30]);
+    }
+    assert_eq!(values, [10, 20, 30]);
+
+    let mut data: MuCow<[i32]> = MuCow::Owned(vec![1, 2, 3]);
+    assert_eq!((&data).into_iter().count(), 3);
+    for x in &mut data {
+        *x += 1;
+    }
+    let ptr = data.as_ptr();
+    let iter = data.into_iter();
+    assert_eq!(iter.as_slice().as_ptr(), ptr);
+    assert_eq!(iter.collect::<Vec<_>>(), [2, 3, 4]);
+}
+
+#[test]
+fn collect_string_from_mucows() {
+    let mut first = String::with_capacity(32);
+    first.push('a');
+    let ptr = first.as_ptr();
+    let mut second = String::from("b");
+    let s: String = vec![
+        MuCow::Owned(first),
+        MuCow::Borrowed(second.as_mut_str()),
+        MuCow::Owned(String::from("c")),
+    ].into_iter().collect();
+    assert_eq!(s, "abc");
+    assert_eq!(s.as_ptr(), ptr);
+
+    let s: String = std::iter::empty::<MuCow<str>>().collect();
+    assert!(s.is_empty());
+
+    let mut s = String::from(">");
+    s.extend(vec![MuCow::Borrowed(second.as_mut_str()), MuCow::Owned(String::from("d"))]);
+    assert_eq!(s, ">bd");
+    assert_eq!(second, "b");
+}
+
+#[test]
+fn collect_vec_from_mucows() {
+    let mut first = Vec::with_capacity(16);
+    first.push(1);
+    let ptr = first.as_ptr();
+    let mut second = [2, 3];
+    let v: Vec<i32> = vec![
+        MuCow::Owned(first),
+        MuCow::Borrowed(&mut second[..]),
+        MuCow::Owned(vec![4]),
+    ].into_iter().collect();
+    assert_eq!(v, [1, 2, 3, 4]);
+    assert_eq!(v.as_ptr(), ptr);
+
+    let mut v = vec![0];
+    v.extend(vec![MuCow::Borrowed(&mut second[..]), MuCow::Owned(vec![5])]);
+    assert_eq!(v, [0, 2, 3, 5]);
+}
+
+#[test]
+fn collect_round_trip() {
+    let original = vec![3, 1, 2];
+    let data: MuCow<Vec<i32>> = original.iter().copied().collect();
+    assert!(data.is_owned());
+    let back: Vec<i32> = data.iter().copied().collect();
+    assert_eq!(back, original);
+
+    let text = "héllo wörld";
+    let data: MuCow<str> = text.chars().collect();
+    let back: String = data.chars().collect();
+    assert_eq!(back, text);
+}
+
+#[test]
+fn extend_str_promotes_once() {
+    let mut buf = String::from("hello");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    assert_no_alloc(|| data.extend(std::iter::empty::<char>()));
+    assert_no_alloc(|| data.extend(Vec::<&str>::new()));
+    assert!(data.is_borrowed());
+
+    let (_, n) = allocations(|| data.extend(std::iter::repeat_n('!', 3)));
+    assert_eq!(n, 1);
+    assert!(data.is_owned());
+    let ptr = data.as_ptr();
+    data.extend(std::iter::empty::<String>());
+    assert_eq!(data.as_ptr(), ptr);
+    assert_eq!(data, "hello!!!");
+    drop(data);
+    assert_eq!(buf, "hello");
+}
+
+#[test]
+fn extend_slice_promotes_once() {
+    let mut values = [1, 2, 3];
+    let mut data = MuCow::Borrowed(&mut values[..]);
+    assert_no_alloc(|| data.extend(std::iter::empty::<i32>()));
+    assert_no_alloc(|| data.extend(&[]));
+    assert!(data.is_borrowed());
+
+    let more = vec![4, 5];
+    let (_, n) = allocations(|| data.extend(more));
+    assert_eq!(n, 1);
+    assert!(data.is_owned());
+    let (_, n) = allocations(|| data.extend(&[6]));
+    assert!(n <= 1);
+    assert_eq!(data, [1, 2, 3, 4, 5, 6]);
+    assert_eq!(values, [1, 2, 3]);
+}
+
+#[test]
+fn into_items_borrowed() {
+    let mut values = [1, 2, 3, 4];
+    {
+        let mut items = assert_no_alloc(|| MuCow::Borrowed(&mut values[..]).into_items());
+        assert_eq!(items.len(), 4);
+        let mut first = items.next().unwrap();
+        let mut last = items.next_back().unwrap();
+        assert!(first.is_borrowed() && last.is_borrowed());
+        assert_eq!(items.len(), 2);
+        *first = 10;
+        *last = 40;
+        for mut item in items {
+            *item += 100;
+        }
+    }
+    assert_eq!(values, [10, 102, 103, 40]);
+}
+
+#[test]
+fn into_items_owned() {
+    let mut items = MuCow::<[String]>::Owned(vec![String::from("a"), String::from("b")]).into_items();
+    assert_eq!(items.len(), 2);
+    let last = items.next_back().unwrap();
+    assert!(last.is_owned());
+    assert_eq!(*last, "b");
+    assert_eq!(items.len(), 1);
+    assert_eq!(*items.next().unwrap(), "a");
+    assert!(items.next().is_none());
+    assert!(items.next_back().is_none());
+    assert_eq!(items.len(), 0);
+}
+
+#[test]
+fn chunks_of_borrowed_data_do_not_allocate() {
+    let mut values = [1, 2, 3, 4, 5];
+    assert_no_alloc(|| {
+        let mut chunks = MuCow::Borrowed(&mut values[..]).into_chunks(2);
+        assert_eq!(chunks.len(), 3);
+        for mut chunk in chunks.by_ref().take(2) {
+            assert!(chunk.is_borrowed());
+            chunk[0] *= 10;
+        }
+        assert_eq!(chunks.next().unwrap(), [5]);
+        assert!(chunks.next().is_none());
+    });
+    assert_eq!(values, [10, 2, 30, 4, 5]);
+
+    assert_no_alloc(|| {
+        let mut exact = MuCow::Borrowed(&mut values[..]).into_chunks_exact(2);
+        assert_eq!(exact.len(), 2);
+        assert_eq!(exact.next().unwrap(), [10, 2]);
+        assert_eq!(exact.next().unwrap(), [30, 4]);
+        assert!(exact.next().is_none());
+
+        let mut rchunks = MuCow::Borrowed(&mut values[..]).into_rchunks(2);
+        assert_eq!(rchunks.next().unwrap(), [4, 5]);
+        assert_eq!(rchunks.next().unwrap(), [2, 30]);
+        assert_eq!(rchunks.next().unwrap(), [10]);
+        assert!(rchunks.next().is_none());
+
+        let mut pieces = MuCow::Borrowed(&mut values[..]).into_split(|x| *x == 30);
+        assert_eq!(pieces.next().unwrap(), [10, 2]);
+        assert_eq!(pieces.next().unwrap(), [4, 5]);
+        assert!(pieces.next().is_none());
+    });
+}
+
+#[test]
+fn chunks_of_owned_data() {
+    let chunks: Vec<MuCow<[i32]>> = MuCow::<[i32]>::Owned(vec![1, 2, 3, 4, 5]).into_chunks(2).collect();
+    assert!(chunks.iter().all(|c| c.is_owned()));
+    assert_eq!(chunks, [&[1, 2][..], &[3, 4], &[5]]);
+
+    let exact: Vec<MuCow<[i32]>> = MuCow::<[i32]>::Owned(vec![1, 2, 3, 4, 5]).into_chunks_exact(2).collect();
+    assert_eq!(exact, [&[1, 2][..], &[3, 4]]);
+
+    let rchunks: Vec<MuCow<[i32]>> = MuCow::<[i32]>::Owned(vec![1, 2, 3, 4, 5]).into_rchunks(2).collect();
+    assert_eq!(rchunks, [&[4, 5][..], &[2, 3], &[1]]);
+
+    let pieces: Vec<MuCow<[i32]>> = MuCow::<[i32]>::Owned(vec![0, 1, 0]).into_split(|x| *x == 0).collect();
+    assert_eq!(pieces, [&[][..], &[1], &[]]);
+}
+
+#[test]
+fn chunks_of_empty_data() {
+    let mut values: [i32; 0] = [];
+    assert_eq!(MuCow::Borrowed(&mut values[..]).into_chunks(2).count(), 0);
+    assert_eq!(MuCow::Borrowed(&mut values[..]).into_chunks_exact(2).count(), 0);
+    assert_eq!(MuCow::Borrowed(&mut values[..]).into_rchunks(2).count(), 0);
+    assert_eq!(MuCow::<[i32]>::Owned(Vec::new()).into_chunks(2).count(), 0);
+    assert_eq!(MuCow::<[i32]>::Owned(Vec::new()).into_chunks_exact(2).count(), 0);
+    assert_eq!(MuCow::<[i32]>::Owned(Vec::new()).into_rchunks(2).len(), 0);
+
+    let pieces: Vec<MuCow<[i32]>> = MuCow::Borrowed(&mut values[..]).into_split(|_| true).collect();
+    assert_eq!(pieces.len(), 1);
+    assert!(pieces[0].is_empty());
+    let pieces: Vec<MuCow<[i32]>> = MuCow::<[i32]>::Owned(Vec::new()).into_split(|_| true).collect();
+    assert_eq!(pieces.len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "chunk size must be non-zero")]
+fn chunks_of_zero_size_panic() {
+    let mut values = [1, 2];
+    MuCow::Borrowed(&mut values[..]).into_chunks(0);
+}
+
+#[test]
+#[should_panic(expected = "chunk size must be non-zero")]
+fn chunks_exact_of_zero_size_panic() {
+    MuCow::<[i32]>::Owned(vec![1]).into_chunks_exact(0);
+}
+
+#[test]
+#[should_panic(expected = "chunk size must be non-zero")]
+fn rchunks_of_zero_size_panic() {
+    MuCow::<[i32]>::Owned(vec![1]).into_rchunks(0);
+}
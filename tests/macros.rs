@@ -0,0 +1,68 @@
+extern crate mucow;
+
+use mucow::{mucow, MuCow};
+
+#[test]
+fn empty_owns_an_empty_vec() {
+    let data: MuCow<[i32]> = mucow![];
+    assert!(data.is_owned());
+    assert!(data.is_empty());
+
+    let data: MuCow<Vec<i32>> = mucow!();
+    assert!(data.is_owned());
+    assert!(data.is_empty());
+}
+
+#[test]
+fn mut_ref_borrows() {
+    let mut buf = String::from("hello");
+    {
+        let mut data: MuCow<str> = mucow!(&mut buf);
+        assert!(data.is_borrowed());
+        data.make_ascii_uppercase();
+    }
+    assert_eq!(buf, "HELLO");
+
+    let mut values = [1, 2, 3];
+    let data: MuCow<[i32]> = mucow!(&mut values[..]);
+    assert!(data.is_borrowed());
+}
+
+#[test]
+fn single_expression_owns_it() {
+    let data: MuCow<str> = mucow!(String::from("hello"));
+    assert!(data.is_owned());
+    assert_eq!(data, "hello");
+
+    let v = vec![1, 2, 3];
+    let data: MuCow<[i32]> = mucow![v];
+    assert!(data.is_owned());
+    assert_eq!(data, [1, 2, 3]);
+}
+
+#[test]
+fn list_owns_a_vec_of_the_elements() {
+    let data: MuCow<[i32]> = mucow![1, 2, 3];
+    assert!(data.is_owned());
+    assert_eq!(data, [1, 2, 3]);
+
+    let data: MuCow<[i32]> = mucow![1, 2, 3,];
+    assert_eq!(data, [1, 2, 3]);
+
+    let data: MuCow<Vec<&str>> = mucow!["a", "b"];
+    assert_eq!(*data, vec!["a", "b"]);
+}
+
+#[test]
+fn trailing_comma_does_not_change_a_single_expression() {
+    let v = vec![1, 2, 3];
+    let data: MuCow<[i32]> = mucow![v,];
+    assert!(data.is_owned());
+    assert_eq!(data, [1, 2, 3]);
+
+    let data: MuCow<str> = mucow!(String::from("hello"),);
+    assert_eq!(data, "hello");
+
+    let data: MuCow<[i32]> = mucow!(vec![7]);
+    assert_eq!(data, [7]);
+}
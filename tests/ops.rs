@@ -0,0 +1,150 @@
+extern crate mucow;
+
+use std::borrow::Cow;
+
+use mucow::MuCow;
+
+mod common;
+use common::assert_no_alloc;
+
+#[test]
+fn index_slice() {
+    let mut values = [1, 2, 3, 4];
+    {
+        let mut data = MuCow::Borrowed(&mut values[..]);
+        assert_eq!(data[0], 1);
+        assert_eq!(data[1..3], [2, 3]);
+        assert_eq!(data[2..], [3, 4]);
+        assert_eq!(data[..], [1, 2, 3, 4]);
+        data[0] = 10;
+        data[2..].copy_from_slice(&[30, 40]);
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(values, [10, 2, 30, 40]);
+
+    let mut data: MuCow<Vec<i32>> = MuCow::Owned(vec![1, 2, 3]);
+    data[1] = 20;
+    assert_eq!(data[..2], [1, 20]);
+}
+
+#[test]
+fn index_str() {
+    let mut buf = String::from("héllo");
+    {
+        let mut data = MuCow::Borrowed(buf.as_mut_str());
+        assert_eq!(&data[..1], "h");
+        assert_eq!(&data[1..3], "é");
+        assert_eq!(&data[3..], "llo");
+        assert_eq!(&data[..=2], "hé");
+        data[3..].make_ascii_uppercase();
+    }
+    assert_eq!(buf, "héLLO");
+}
+
+#[test]
+#[should_panic]
+fn index_out_of_bounds_panics() {
+    let data: MuCow<[i32]> = MuCow::Owned(vec![1]);
+    let _ = data[1];
+}
+
+#[test]
+fn add_str_promotes_borrowed_data() {
+    let mut buf = String::from("hello");
+    let data = MuCow::Borrowed(buf.as_mut_str()) + ", " + Cow::Borrowed("wor");
+    let mut rest = String::from("ld");
+    let data = data + MuCow::Borrowed(rest.as_mut_str());
+    assert!(data.is_owned());
+    assert_eq!(data, "hello, world");
+    assert_eq!(buf, "hello");
+
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    data += "!";
+    assert!(data.is_owned());
+    data += Cow::Owned(String::from("?"));
+    data += MuCow::<str>::Owned(String::from("."));
+    assert_eq!(data, "hello!?.");
+}
+
+#[test]
+fn add_empty_str_stays_borrowed() {
+    let mut buf = String::from("hello");
+    let mut empty = String::new();
+    let mut data = assert_no_alloc(|| MuCow::Borrowed(buf.as_mut_str()) + "");
+    assert_no_alloc(|| {
+        data += "";
+        data += Cow::Borrowed("");
+        data += MuCow::Borrowed(empty.as_mut_str());
+    });
+    assert!(data.is_borrowed());
+    assert_eq!(data, "hello");
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Meters(i32);
+
+impl std::ops::AddAssign<i32> for Meters {
+    fn add_assign(&mut self, rhs: i32) {
+        self.0 += rhs;
+    }
+}
+
+impl std::ops::Neg for Meters {
+    type Output = Meters;
+
+    fn neg(self) -> Meters {
+        Meters(-self.0)
+    }
+}
+
+#[test]
+fn op_assign_on_integers() {
+    let mut value = 6_u32;
+    {
+        let mut data = MuCow::Borrowed(&mut value);
+        data += 4;
+        data -= 2;
+        data *= 3;
+        data /= 2;
+        data %= 7;
+        data <<= 2;
+        data >>= 1;
+        data |= 1;
+        data &= 0b1101;
+        data ^= 0b0100;
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(value, 13);
+
+    let mut data: MuCow<u32> = MuCow::Owned(6);
+    data += 4;
+    data <<= 1;
+    assert!(data.is_owned());
+    assert_eq!(*data, 20);
+}
+
+#[test]
+fn neg_and_not_on_integers() {
+    let mut value = 5_i32;
+    assert_eq!(-MuCow::Borrowed(&mut value), -5);
+    assert_eq!(!MuCow::Borrowed(&mut value), !5);
+    assert_eq!(value, 5);
+    assert_eq!(-MuCow::<i32>::Owned(7), -7);
+    assert!(!MuCow::<bool>::Owned(false));
+}
+
+#[test]
+fn ops_on_a_newtype() {
+    let mut distance = Meters(3);
+    {
+        let mut data = MuCow::Borrowed(&mut distance);
+        data += 2;
+        assert_eq!(-data, Meters(-5));
+    }
+    assert_eq!(distance, Meters(5));
+
+    let mut data: MuCow<Meters> = MuCow::Owned(Meters(1));
+    data += 1;
+    assert_eq!(*data, Meters(2));
+    assert_eq!(-data, Meters(-2));
+}
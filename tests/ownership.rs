@@ -0,0 +1,645 @@
+extern crate mucow;
+
+use mucow::MuCow;
+
+mod common;
+use common::{allocations, assert_no_alloc};
+
+#[test]
+fn variant_accessors_on_str() {
+    let mut buf = String::from("hello");
+    {
+        let mut data = MuCow::Borrowed(buf.as_mut_str());
+        assert!(data.is_borrowed());
+        assert!(!data.is_owned());
+        assert_eq!(data.as_owned(), None);
+        assert_eq!(data.as_owned_mut(), None);
+        data.as_borrowed_mut().unwrap().make_ascii_uppercase();
+    }
+    assert_eq!(buf, "HELLO");
+
+    let mut data: MuCow<str> = MuCow::Owned(String::from("hello"));
+    assert!(!data.is_borrowed());
+    assert!(data.is_owned());
+    assert_eq!(data.as_owned().map(String::as_str), Some("hello"));
+    assert!(data.as_borrowed_mut().is_none());
+    data.as_owned_mut().unwrap().push_str(", world");
+    assert_eq!(data, "hello, world");
+}
+
+#[test]
+fn variant_accessors_on_bytes() {
+    let mut buf = [1u8, 2, 3];
+    {
+        let mut data = MuCow::Borrowed(&mut buf[..]);
+        assert!(data.is_borrowed());
+        assert!(!data.is_owned());
+        assert_eq!(data.as_owned(), None);
+        assert_eq!(data.as_owned_mut(), None);
+        data.as_borrowed_mut().unwrap()[0] = 10;
+    }
+    assert_eq!(buf, [10, 2, 3]);
+
+    let mut data: MuCow<[u8]> = MuCow::Owned(vec![1, 2, 3]);
+    assert!(!data.is_borrowed());
+    assert!(data.is_owned());
+    assert_eq!(data.as_owned(), Some(&vec![1, 2, 3]));
+    assert!(data.as_borrowed_mut().is_none());
+    data.as_owned_mut().unwrap().push(4);
+    assert_eq!(data, [1, 2, 3, 4]);
+}
+
+#[test]
+fn to_mut_promotes_borrowed_data() {
+    let mut buf = String::from("hello");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    data.to_mut().push_str(", world");
+    assert!(data.is_owned());
+    assert_eq!(data, "hello, world");
+    data.to_mut().insert(0, '>');
+    assert_eq!(data.to_mut().len(), 13);
+    assert_eq!(data, ">hello, world");
+    drop(data);
+    assert_eq!(buf, "hello");
+}
+
+#[test]
+fn to_mut_keeps_owned_data() {
+    let mut data: MuCow<str> = MuCow::Owned(String::with_capacity(16));
+    let ptr = data.as_ptr();
+    data.to_mut().push_str("hello");
+    assert!(data.is_owned());
+    assert_eq!(data.as_ptr(), ptr);
+    assert_eq!(data, "hello");
+}
+
+#[test]
+fn make_owned_clones_only_once() {
+    let mut buf = String::from("hello");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    let (_, n) = allocations(|| {
+        data.make_owned();
+    });
+    assert_eq!(n, 1);
+    assert!(data.is_owned());
+    let ptr = data.as_ptr();
+    let (_, n) = allocations(|| {
+        data.make_owned().make_ascii_uppercase();
+    });
+    assert_eq!(n, 0);
+    assert_eq!(data.as_ptr(), ptr);
+    assert_eq!(data, "HELLO");
+    drop(data);
+    assert_eq!(buf, "hello");
+}
+
+#[test]
+fn take_clones_borrowed_data_and_leaves_it_intact() {
+    let mut buf = String::from("hello");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    assert_eq!(data.take(), "hello");
+    assert!(data.is_owned());
+    assert_eq!(data, "");
+    drop(data);
+    assert_eq!(buf, "hello");
+
+    let mut values = [1, 2, 3];
+    let mut data = MuCow::Borrowed(&mut values[..]);
+    assert_eq!(data.take(), [1, 2, 3]);
+    assert!(data.is_empty());
+    drop(data);
+    assert_eq!(values, [1, 2, 3]);
+
+    let mut value = 5;
+    {
+        let mut data = MuCow::Borrowed(&mut value);
+        assert_eq!(data.take(), 5);
+        assert_eq!(*data, 0);
+    }
+    assert_eq!(value, 5);
+}
+
+#[test]
+fn take_moves_owned_data() {
+    let owned = String::from("hello");
+    let ptr = owned.as_ptr();
+    let mut data: MuCow<str> = MuCow::Owned(owned);
+    let taken = data.take();
+    assert_eq!(taken.as_ptr(), ptr);
+    assert_eq!(data, "");
+
+    let mut data: MuCow<[i32]> = MuCow::Owned(vec![1, 2, 3]);
+    assert_eq!(data.take(), [1, 2, 3]);
+    assert!(data.is_empty());
+
+    let mut data: MuCow<i32> = MuCow::Owned(5);
+    assert_eq!(data.take(), 5);
+    assert_eq!(*data, 0);
+}
+
+#[test]
+fn replace_returns_previous_data() {
+    let mut buf = String::from("hello");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    assert_eq!(data.replace(String::from("world")), "hello");
+    assert_eq!(data.replace(String::from("again")), "world");
+    assert_eq!(data, "again");
+    drop(data);
+    assert_eq!(buf, "hello");
+
+    let mut values = [1, 2];
+    let mut data = MuCow::Borrowed(&mut values[..]);
+    assert_eq!(data.replace(vec![3]), [1, 2]);
+    assert_eq!(data, [3]);
+
+    let mut data: MuCow<i32> = MuCow::Owned(1);
+    assert_eq!(data.replace(2), 1);
+    assert_eq!(*data, 2);
+}
+
+#[test]
+fn swap_exchanges_the_data() {
+    let mut a = String::from("left");
+    let mut data = MuCow::Borrowed(a.as_mut_str());
+    let mut other: MuCow<str> = MuCow::Owned(String::from("right"));
+    data.swap(&mut other);
+    assert_eq!(data, "right");
+    assert_eq!(other, "left");
+
+    let mut values = [1, 2];
+    let mut data = MuCow::Borrowed(&mut values[..]);
+    let mut other: MuCow<[i32]> = MuCow::Owned(vec![3]);
+    data.swap(&mut other);
+    assert_eq!(data, [3]);
+    assert_eq!(other, [1, 2]);
+
+    let mut x = 1;
+    let mut y = 2;
+    {
+        let mut data = MuCow::Borrowed(&mut x);
+        let mut other = MuCow::Borrowed(&mut y);
+        data.swap(&mut other);
+        *data += 10;
+    }
+    assert_eq!((x, y), (1, 12));
+}
+
+#[test]
+fn reborrow_writes_through_to_self() {
+    let mut data: MuCow<str> = MuCow::Owned(String::from("hello"));
+    {
+        let mut short = data.reborrow();
+        assert!(short.is_borrowed());
+        short.make_ascii_uppercase();
+    }
+    assert!(data.is_owned());
+    assert_eq!(data, "HELLO");
+}
+
+#[test]
+fn into_static_can_be_sent_to_a_thread() {
+    let mut buf = String::from("hello");
+    let data = MuCow::Borrowed(buf.as_mut_str()).into_static();
+    let handle = std::thread::spawn(move || {
+        let mut data = data;
+        data.push_str(", world");
+        data
+    });
+    let data = handle.join().unwrap();
+    assert!(data.is_owned());
+    assert_eq!(data, "hello, world");
+    assert_eq!(buf, "hello");
+}
+
+#[test]
+fn try_into_borrowed_never_clones() {
+    let mut buf = String::from("hello");
+    let ptr = buf.as_ptr();
+    let borrowed = MuCow::Borrowed(buf.as_mut_str()).try_into_borrowed().unwrap();
+    assert_eq!(borrowed.as_ptr(), ptr);
+
+    let owned = String::from("hello");
+    let ptr = owned.as_ptr();
+    let err = MuCow::<str>::Owned(owned).try_into_borrowed().unwrap_err();
+    assert_eq!(err.as_ptr(), ptr);
+}
+
+#[test]
+fn try_into_owned_lossless_never_clones() {
+    let owned = String::from("hello");
+    let ptr = owned.as_ptr();
+    let owned = MuCow::<str>::Owned(owned).try_into_owned_lossless().unwrap();
+    assert_eq!(owned.as_ptr(), ptr);
+
+    let mut buf = String::from("hello");
+    let ptr = buf.as_ptr();
+    let err = MuCow::Borrowed(buf.as_mut_str()).try_into_owned_lossless().unwrap_err();
+    err.make_ascii_uppercase();
+    assert_eq!(err.as_ptr(), ptr);
+    assert_eq!(buf, "HELLO");
+}
+
+fn leak_static(s: &str) -> &'static mut str {
+    MuCow::<'static, str>::Owned(s.to_owned()).leak()
+}
+
+#[test]
+fn leak_str() {
+    let leaked = leak_static("hello");
+    leaked.make_ascii_uppercase();
+    assert_eq!(leaked, "HELLO");
+
+    let mut buf = String::from("hello");
+    let ptr = buf.as_ptr();
+    let leaked = MuCow::Borrowed(buf.as_mut_str()).leak();
+    assert_eq!(leaked.as_ptr(), ptr);
+    leaked.make_ascii_uppercase();
+    assert_eq!(buf, "HELLO");
+}
+
+#[test]
+fn leak_slice() {
+    let leaked: &'static mut [i32] = MuCow::<[i32]>::Owned(vec![1, 2, 3]).leak();
+    leaked[0] = 10;
+    assert_eq!(leaked, [10, 2, 3]);
+
+    let mut values = [1, 2, 3];
+    let leaked = MuCow::Borrowed(&mut values[..]).leak();
+    leaked[0] = 10;
+    assert_eq!(values, [10, 2, 3]);
+}
+
+#[test]
+fn kind_reports_the_variant() {
+    use mucow::MuCowKind;
+
+    let mut buf = String::from("hello");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    assert_eq!(data.kind(), MuCowKind::Borrowed);
+    data.make_owned();
+    assert_eq!(data.kind(), MuCowKind::Owned);
+    assert_ne!(MuCowKind::Borrowed, MuCowKind::Owned);
+}
+
+#[test]
+fn unwrap_borrowed_returns_the_borrow() {
+    let mut buf = String::from("hello");
+    MuCow::Borrowed(buf.as_mut_str()).unwrap_borrowed().make_ascii_uppercase();
+    assert_eq!(buf, "HELLO");
+    MuCow::Borrowed(buf.as_mut_str()).expect_borrowed("not borrowed").make_ascii_lowercase();
+    assert_eq!(buf, "hello");
+}
+
+#[test]
+#[should_panic(expected = "called `MuCow::unwrap_borrowed()` on an `Owned` value: \"hello\"")]
+fn unwrap_borrowed_panics_on_owned() {
+    MuCow::<str>::Owned(String::from("hello")).unwrap_borrowed();
+}
+
+#[test]
+#[should_panic(expected = "buffer was cloned: [1, 2]")]
+fn expect_borrowed_panics_on_owned() {
+    MuCow::<[i32]>::Owned(vec![1, 2]).expect_borrowed("buffer was cloned");
+}
+
+#[test]
+fn into_borrowed_unchecked_on_borrowed() {
+    let mut buf = String::from("hello");
+    let data = MuCow::Borrowed(buf.as_mut_str());
+    // SAFETY: the data is borrowed.
+    let borrowed = unsafe { data.into_borrowed_unchecked() };
+    borrowed.make_ascii_uppercase();
+    assert_eq!(buf, "HELLO");
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "called `MuCow::into_borrowed_unchecked()` on an `Owned` value")]
+fn into_borrowed_unchecked_panics_on_owned_in_debug() {
+    // This breaks the safety contract on purpose. With debug assertions
+    // enabled it panics instead of reaching undefined behaviour.
+    unsafe { MuCow::<str>::Owned(String::from("hello")).into_borrowed_unchecked() };
+}
+
+#[test]
+fn map_string_to_str() {
+    let mut buf = String::from("hello");
+    {
+        let mut data = MuCow::<String>::Borrowed(&mut buf)
+            .map(String::as_mut_str, |owned| owned);
+        assert!(data.is_borrowed());
+        data.make_ascii_uppercase();
+    }
+    assert_eq!(buf, "HELLO");
+
+    let owned = String::from("hello");
+    let ptr = owned.as_ptr();
+    let data: MuCow<str> = MuCow::<String>::Owned(owned).map(String::as_mut_str, |owned| owned);
+    assert!(data.is_owned());
+    assert_eq!(data.as_ptr(), ptr);
+}
+
+#[test]
+fn map_str_to_bytes() {
+    let mut buf = String::from("hello");
+    let ptr = buf.as_ptr();
+    // SAFETY: the bytes are only read.
+    let data: MuCow<[u8]> = MuCow::Borrowed(buf.as_mut_str())
+        .map(|s| unsafe { s.as_bytes_mut() }, String::into_bytes);
+    assert!(data.is_borrowed());
+    assert_eq!(data.as_ptr(), ptr);
+    assert_eq!(data, *b"hello");
+
+    let data: MuCow<[u8]> = MuCow::<str>::Owned(String::from("hi"))
+        .map(|s| unsafe { s.as_bytes_mut() }, String::into_bytes);
+    assert!(data.is_owned());
+    assert_eq!(data, *b"hi");
+}
+
+#[test]
+fn into_owned_into_reuses_the_target() {
+    let mut buf = String::from("hello");
+    let mut target = String::with_capacity(32);
+    let ptr = target.as_ptr();
+    let (_, n) = allocations(|| MuCow::Borrowed(buf.as_mut_str()).into_owned_into(&mut target));
+    assert_eq!(n, 0);
+    assert_eq!(target, "hello");
+    assert_eq!(target.as_ptr(), ptr);
+    assert_eq!(target.capacity(), 32);
+
+    let owned = String::from("world");
+    let ptr = owned.as_ptr();
+    MuCow::<str>::Owned(owned).into_owned_into(&mut target);
+    assert_eq!(target, "world");
+    assert_eq!(target.as_ptr(), ptr);
+}
+
+#[test]
+fn clone_into_owned_reuses_the_target() {
+    let mut values = [1, 2, 3];
+    let data = MuCow::Borrowed(&mut values[..]);
+    let mut target = Vec::with_capacity(8);
+    target.push(9);
+    let ptr = target.as_ptr();
+    let (_, n) = allocations(|| data.clone_into_owned(&mut target));
+    assert_eq!(n, 0);
+    assert_eq!(target, [1, 2, 3]);
+    assert_eq!(target.as_ptr(), ptr);
+    assert!(data.is_borrowed());
+
+    let data: MuCow<[i32]> = MuCow::Owned(vec![4, 5]);
+    let (_, n) = allocations(|| data.clone_into_owned(&mut target));
+    assert_eq!(n, 0);
+    assert_eq!(target, [4, 5]);
+    assert_eq!(target.as_ptr(), ptr);
+    assert_eq!(data, [4, 5]);
+}
+
+#[test]
+fn promote_with_uses_f_only_for_borrowed_data() {
+    let mut buf = String::from("hello");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    let mut calls = 0;
+    data.promote_with(|s| {
+        calls += 1;
+        let mut owned = String::with_capacity(64);
+        owned.push_str(s);
+        owned
+    });
+    assert_eq!(calls, 1);
+    assert!(data.is_owned());
+    assert_eq!(data.as_owned().unwrap().capacity(), 64);
+
+    data.promote_with(|_| {
+        calls += 1;
+        String::new()
+    }).push('!');
+    assert_eq!(calls, 1);
+    assert_eq!(data, "hello!");
+}
+
+#[test]
+fn into_owned_with_uses_f_only_for_borrowed_data() {
+    let mut buf = String::from("hello");
+    let owned = MuCow::Borrowed(buf.as_mut_str()).into_owned_with(str::to_uppercase);
+    assert_eq!(owned, "HELLO");
+    assert_eq!(buf, "hello");
+
+    let owned = MuCow::<str>::Owned(String::from("hello"))
+        .into_owned_with(|_| panic!("f called on owned data"));
+    assert_eq!(owned, "hello");
+}
+
+#[test]
+fn map_projects_str_without_cloning() {
+    let mut buf = String::from("key=value");
+    {
+        let mut value = assert_no_alloc(|| {
+            MuCow::Borrowed(buf.as_mut_str()).map(|s| &mut s[4..], |s| s[4..].to_owned())
+        });
+        assert!(value.is_borrowed());
+        assert_eq!(value, "value");
+        value.make_ascii_uppercase();
+    }
+    assert_eq!(buf, "key=VALUE");
+
+    let value = MuCow::<str>::Owned(String::from("key=value"))
+        .map(|s| &mut s[4..], |s| s[4..].to_owned());
+    assert!(value.is_owned());
+    assert_eq!(value, "value");
+}
+
+#[test]
+fn map_projects_slice_without_cloning() {
+    let mut values = [1, 2, 3];
+    {
+        let mut first: MuCow<i32> = assert_no_alloc(|| {
+            MuCow::Borrowed(&mut values[..]).map(|s| &mut s[0], |v| v[0])
+        });
+        assert!(first.is_borrowed());
+        *first = 10;
+    }
+    assert_eq!(values, [10, 2, 3]);
+
+    let last: MuCow<i32> = MuCow::<[i32]>::Owned(vec![1, 2, 3])
+        .map(|s| &mut s[2], |mut v| v.pop().unwrap());
+    assert!(last.is_owned());
+    assert_eq!(*last, 3);
+}
+
+#[test]
+fn map_owned_chain() {
+    let mut buf = String::from(" 42 ");
+    let data = MuCow::Borrowed(buf.as_mut_str())
+        .map_owned(|s| s.trim().to_owned())
+        .map_owned(|mut s| {
+            s.insert(0, '-');
+            s
+        })
+        .try_map_owned(|s| s.parse::<i32>().map(|n| (n * 2).to_string()))
+        .unwrap();
+    assert!(data.is_owned());
+    assert_eq!(data, "-84");
+    assert_eq!(buf, " 42 ");
+
+    let err = MuCow::<str>::Owned(String::from("x"))
+        .map_owned(|s| s + "y")
+        .try_map_owned(|s| s.parse::<i32>().map(|n| n.to_string()));
+    assert!(err.is_err());
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Config {
+    name: String,
+    retries: u32,
+}
+
+#[test]
+fn set_and_assign_from_write_through() {
+    let mut config = Config { name: String::from("default"), retries: 1 };
+    {
+        let mut data = MuCow::Borrowed(&mut config);
+        data.set(Config { name: String::from("set"), retries: 2 });
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(config, Config { name: String::from("set"), retries: 2 });
+
+    let other = Config { name: String::from("assigned"), retries: 3 };
+    {
+        let mut data = MuCow::Borrowed(&mut config);
+        data.assign_from(&other);
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(config, other);
+
+    let mut data: MuCow<Config> = MuCow::Owned(other.clone());
+    data.set(Config { name: String::from("owned"), retries: 4 });
+    assert!(data.is_owned());
+    assert_eq!(data.retries, 4);
+}
+
+#[test]
+fn make_owned_is_idempotent() {
+    let mut values = [1, 2, 3];
+    let mut data = MuCow::Borrowed(&mut values[..]);
+    data.make_owned();
+    assert!(data.is_owned());
+    data.make_owned();
+    assert!(data.is_owned());
+    assert_eq!(data, [1, 2, 3]);
+
+    let mut data: MuCow<[i32]> = MuCow::Owned(vec![4]);
+    data.make_owned().push(5);
+    data.make_owned();
+    assert!(data.is_owned());
+    assert_eq!(data, [4, 5]);
+}
+
+#[test]
+fn replace_owned_never_clones() {
+    let mut buf = String::from("hello");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    let (previous, n) = allocations(|| data.replace_owned(String::new()));
+    assert_eq!(n, 0);
+    assert_eq!(previous, None);
+    assert!(data.is_owned());
+    assert_eq!(data, "");
+
+    let owned = String::from("world");
+    let ptr = owned.as_ptr();
+    let mut data: MuCow<str> = MuCow::Owned(owned);
+    let previous = data.replace_owned(String::from("again")).unwrap();
+    assert_eq!(previous.as_ptr(), ptr);
+    assert_eq!(previous, "world");
+    assert!(data.is_owned());
+    assert_eq!(data, "again");
+    drop(data);
+    assert_eq!(buf, "hello");
+}
+
+#[test]
+fn swap_all_variant_combinations() {
+    let mut a = String::from("a");
+    let mut b = String::from("b");
+    {
+        let mut x = MuCow::Borrowed(a.as_mut_str());
+        let mut y = MuCow::Borrowed(b.as_mut_str());
+        assert_no_alloc(|| x.swap(&mut y));
+        assert!(x.is_borrowed() && y.is_borrowed());
+        x.make_ascii_uppercase();
+        assert_eq!((&*x, &*y), ("B", "a"));
+    }
+    assert_eq!((&*a, &*b), ("a", "B"));
+
+    let mut x = MuCow::Borrowed(a.as_mut_str());
+    let mut y: MuCow<str> = MuCow::Owned(String::from("owned"));
+    assert_no_alloc(|| x.swap(&mut y));
+    assert!(x.is_owned() && y.is_borrowed());
+    assert_eq!((&*x, &*y), ("owned", "a"));
+
+    let mut x: MuCow<str> = MuCow::Owned(String::from("owned"));
+    let mut y = MuCow::Borrowed(b.as_mut_str());
+    assert_no_alloc(|| x.swap(&mut y));
+    assert!(x.is_borrowed() && y.is_owned());
+    assert_eq!((&*x, &*y), ("B", "owned"));
+
+    let mut x: MuCow<str> = MuCow::Owned(String::from("x"));
+    let mut y: MuCow<str> = MuCow::Owned(String::from("y"));
+    assert_no_alloc(|| x.swap(&mut y));
+    assert!(x.is_owned() && y.is_owned());
+    assert_eq!((&*x, &*y), ("y", "x"));
+}
+
+#[test]
+fn into_owned_if_all_combinations() {
+    let mut buf = String::from("hello");
+    let data = MuCow::Borrowed(buf.as_mut_str()).into_owned_if(|s| s.starts_with('h'));
+    assert!(data.is_owned());
+    assert_eq!(data, "hello");
+
+    let data = MuCow::Borrowed(buf.as_mut_str()).into_owned_if(|s| s.is_empty());
+    assert!(data.is_borrowed());
+
+    let owned = String::from("hello");
+    let ptr = owned.as_ptr();
+    let data = MuCow::<str>::Owned(owned.clone()).into_owned_if(|_| true);
+    assert!(data.is_owned());
+    let data = MuCow::<str>::Owned(owned).into_owned_if(|_| false);
+    assert!(data.is_owned());
+    assert_eq!(data.as_ptr(), ptr);
+}
+
+#[test]
+fn from_option_borrows_or_owns() {
+    let mut buf = String::from("given");
+    {
+        let mut data = MuCow::from_option(Some(buf.as_mut_str()), String::from("default"));
+        assert!(data.is_borrowed());
+        data.make_ascii_uppercase();
+    }
+    assert_eq!(buf, "GIVEN");
+
+    let data = MuCow::<[i32]>::from_option(None, vec![1, 2]);
+    assert!(data.is_owned());
+    assert_eq!(data, [1, 2]);
+}
+
+#[test]
+fn from_option_with_calls_f_only_for_none() {
+    let mut calls = 0;
+    let mut buf = String::from("given");
+    let data = MuCow::from_option_with(Some(buf.as_mut_str()), || {
+        calls += 1;
+        String::new()
+    });
+    assert!(data.is_borrowed());
+    assert_eq!(calls, 0);
+
+    let data = MuCow::<str>::from_option_with(None, || {
+        calls += 1;
+        String::from("default")
+    });
+    assert!(data.is_owned());
+    assert_eq!(data, "default");
+    assert_eq!(calls, 1);
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "serde")]
+
+extern crate mucow;
+
+use mucow::MuCow;
+
+#[test]
+fn str_round_trip_from_borrowed() {
+    let mut buf = String::from("hello \"world\"");
+    let data = MuCow::Borrowed(buf.as_mut_str());
+    let json = serde_json::to_string(&data).unwrap();
+    assert_eq!(json, r#""hello \"world\"""#);
+
+    let parsed: MuCow<str> = serde_json::from_str(&json).unwrap();
+    assert!(parsed.is_owned());
+    assert_eq!(parsed, data);
+}
+
+#[test]
+fn str_round_trip_from_owned() {
+    let data: MuCow<str> = MuCow::Owned(String::from("héllo"));
+    let json = serde_json::to_string(&data).unwrap();
+    assert_eq!(json, r#""héllo""#);
+
+    let parsed: MuCow<str> = serde_json::from_str(&json).unwrap();
+    assert!(parsed.is_owned());
+    assert_eq!(parsed, "héllo");
+}
+
+#[test]
+fn bytes_round_trip() {
+    let mut buf = [1u8, 2, 255];
+    let data = MuCow::Borrowed(&mut buf[..]);
+    let json = serde_json::to_string(&data).unwrap();
+    assert_eq!(json, "[1,2,255]");
+
+    let parsed: MuCow<[u8]> = serde_json::from_str(&json).unwrap();
+    assert!(parsed.is_owned());
+    assert_eq!(parsed, [1, 2, 255]);
+
+    let data: MuCow<[u8]> = MuCow::Owned(vec![]);
+    let json = serde_json::to_string(&data).unwrap();
+    assert_eq!(json, "[]");
+    let parsed: MuCow<[u8]> = serde_json::from_str(&json).unwrap();
+    assert!(parsed.is_empty());
+}
+
+#[test]
+fn deserialize_rejects_mismatched_types() {
+    assert!(serde_json::from_str::<MuCow<str>>("[1,2]").is_err());
+    assert!(serde_json::from_str::<MuCow<[u8]>>("[256]").is_err());
+}
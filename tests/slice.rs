@@ -0,0 +1,450 @@
+extern crate mucow;
+
+use mucow::MuCow;
+
+mod common;
+use common::assert_no_alloc;
+
+#[test]
+fn len_and_is_empty() {
+    let mut values = [1, 2, 3];
+    let data = MuCow::Borrowed(&mut values[..]);
+    assert_eq!(MuCow::<[i32]>::len(&data), 3);
+    assert_eq!(data.len(), 3);
+    assert!(!data.is_empty());
+
+    let data: MuCow<[i32]> = MuCow::Owned(Vec::new());
+    assert_eq!(MuCow::<[i32]>::len(&data), 0);
+    assert!(MuCow::<[i32]>::is_empty(&data));
+}
+
+#[test]
+fn try_into_array_borrowed() {
+    let mut values = [1, 2, 3];
+    let ptr = values.as_ptr();
+    {
+        let array = assert_no_alloc(|| MuCow::Borrowed(&mut values[..]).try_into_array::<3>());
+        let mut array = array.unwrap();
+        assert!(array.is_borrowed());
+        assert_eq!(array.as_ptr(), ptr);
+        array[0] = 10;
+    }
+    assert_eq!(values, [10, 2, 3]);
+
+    let err = MuCow::Borrowed(&mut values[..]).try_into_array::<2>().unwrap_err();
+    assert!(err.is_borrowed());
+    assert_eq!(err, [10, 2, 3]);
+
+    let mut empty: [i32; 0] = [];
+    let array = MuCow::Borrowed(&mut empty[..]).try_into_array::<0>().unwrap();
+    assert_eq!(*array, []);
+    assert!(MuCow::Borrowed(&mut values[..]).try_into_array::<0>().is_err());
+}
+
+#[test]
+fn try_into_array_owned() {
+    let array = MuCow::<[i32]>::Owned(vec![1, 2]).try_into_array::<2>().unwrap();
+    assert!(array.is_owned());
+    assert_eq!(*array, [1, 2]);
+
+    let err = MuCow::<[i32]>::Owned(vec![1, 2]).try_into_array::<3>().unwrap_err();
+    assert!(err.is_owned());
+    assert_eq!(err, [1, 2]);
+
+    let array = MuCow::<[i32]>::Owned(Vec::new()).try_into_array::<0>().unwrap();
+    assert_eq!(*array, []);
+}
+
+#[test]
+fn unsize_array() {
+    let mut values = [1, 2, 3];
+    let data = assert_no_alloc(|| MuCow::Borrowed(&mut values).unsize());
+    assert!(data.is_borrowed());
+    assert_eq!(data, [1, 2, 3]);
+
+    let data = MuCow::<[i32; 2]>::Owned([4, 5]).unsize();
+    assert!(data.is_owned());
+    assert_eq!(data, [4, 5]);
+}
+
+#[test]
+fn each_mut_array() {
+    let mut values = [1, 2, 3];
+    {
+        let handles = assert_no_alloc(|| MuCow::Borrowed(&mut values).each_mut());
+        for mut handle in handles {
+            assert!(handle.is_borrowed());
+            *handle *= 10;
+        }
+    }
+    assert_eq!(values, [10, 20, 30]);
+
+    let [a, b] = MuCow::<[i32; 2]>::Owned([1, 2]).each_mut();
+    assert!(a.is_owned() && b.is_owned());
+    assert_eq!((*a, *b), (1, 2));
+}
+
+#[test]
+fn sort_reorders_the_original() {
+    let mut values = [3, 1, 2];
+    assert_no_alloc(|| MuCow::Borrowed(&mut values[..]).sort_unstable());
+    assert_eq!(values, [1, 2, 3]);
+
+    MuCow::Borrowed(&mut values[..]).sort_by(|a, b| b.cmp(a));
+    assert_eq!(values, [3, 2, 1]);
+
+    MuCow::Borrowed(&mut values[..]).sort();
+    assert_eq!(values, [1, 2, 3]);
+
+    let mut pairs = [(1, 'b'), (0, 'a'), (1, 'a')];
+    MuCow::Borrowed(&mut pairs[..]).sort_by_key(|p| p.0);
+    assert_eq!(pairs, [(0, 'a'), (1, 'b'), (1, 'a')]);
+    MuCow::Borrowed(&mut pairs[..]).sort_unstable_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)));
+    assert_eq!(pairs, [(0, 'a'), (1, 'a'), (1, 'b')]);
+
+    let mut data: MuCow<[i32]> = MuCow::Owned(vec![2, 3, 1]);
+    data.sort();
+    assert_eq!(data, [1, 2, 3]);
+}
+
+#[test]
+fn binary_search_on_both_variants() {
+    let mut values = [1, 3, 5, 7];
+    let borrowed = MuCow::Borrowed(&mut values[..]);
+    let owned: MuCow<[i32]> = MuCow::Owned(vec![1, 3, 5, 7]);
+    for data in [&borrowed, &owned] {
+        assert_eq!(data.binary_search(&5), Ok(2));
+        assert_eq!(data.binary_search(&0), Err(0));
+        assert_eq!(data.binary_search(&4), Err(2));
+        assert_eq!(data.binary_search(&8), Err(4));
+        assert_eq!(data.binary_search_by(|x| x.cmp(&7)), Ok(3));
+        assert_eq!(data.binary_search_by_key(&6, |x| x * 2), Ok(1));
+        assert_eq!(data.binary_search_by_key(&5, |x| x * 2), Err(1));
+    }
+}
+
+#[test]
+fn windows_and_chunk_views() {
+    let mut values = [1, 2, 3, 4, 5];
+    let data = MuCow::Borrowed(&mut values[..]);
+
+    let windows: Vec<&[i32]> = data.windows(1).collect();
+    assert_eq!(windows, [[1], [2], [3], [4], [5]]);
+    assert_eq!(data.windows(5).count(), 1);
+    assert_eq!(data.windows(6).count(), 0);
+
+    let chunks: Vec<&[i32]> = data.chunks(2).collect();
+    assert_eq!(chunks, [&[1, 2][..], &[3, 4], &[5]]);
+    let chunks: Vec<&[i32]> = data.chunks(5).collect();
+    assert_eq!(chunks, [&[1, 2, 3, 4, 5][..]]);
+
+    let mut exact = data.chunks_exact(2);
+    assert_eq!(exact.next(), Some(&[1, 2][..]));
+    assert_eq!(exact.next(), Some(&[3, 4][..]));
+    assert_eq!(exact.next(), None);
+    assert_eq!(exact.remainder(), [5]);
+
+    let rchunks: Vec<&[i32]> = data.rchunks(2).collect();
+    assert_eq!(rchunks, [&[4, 5][..], &[2, 3], &[1]]);
+    let mut rexact = data.rchunks_exact(2);
+    assert_eq!(rexact.by_ref().count(), 2);
+    assert_eq!(rexact.remainder(), [1]);
+
+    let owned: MuCow<[i32]> = MuCow::Owned(vec![1, 2, 3, 4]);
+    assert_eq!(owned.chunks(2).count(), 2);
+    assert!(owned.chunks_exact(2).remainder().is_empty());
+}
+
+#[test]
+#[should_panic]
+fn windows_of_zero_size_panic() {
+    let data: MuCow<[i32]> = MuCow::Owned(vec![1]);
+    let _ = data.windows(0);
+}
+
+#[test]
+fn into_split_at_the_ends() {
+    let mut values = [1, 2, 3];
+    let (left, right) = assert_no_alloc(|| MuCow::Borrowed(&mut values[..]).into_split_at(0));
+    assert!(left.is_borrowed() && right.is_borrowed());
+    assert!(left.is_empty());
+    assert_eq!(right, [1, 2, 3]);
+
+    let (left, right) = assert_no_alloc(|| MuCow::Borrowed(&mut values[..]).into_split_at(3));
+    assert_eq!(left, [1, 2, 3]);
+    assert!(right.is_empty());
+
+    let (mut left, mut right) = MuCow::Borrowed(&mut values[..]).into_split_at(1);
+    left[0] = 10;
+    right[1] = 30;
+    assert_eq!(values, [10, 2, 30]);
+
+    let (left, right) = MuCow::<[i32]>::Owned(vec![1, 2, 3]).into_split_at(2);
+    assert!(left.is_owned() && right.is_owned());
+    assert_eq!(left, [1, 2]);
+    assert_eq!(right, [3]);
+}
+
+#[test]
+#[should_panic]
+fn into_split_at_past_the_end_panics() {
+    let mut values = [1, 2];
+    let _ = MuCow::Borrowed(&mut values[..]).into_split_at(3);
+}
+
+#[test]
+fn into_split_first_and_last() {
+    let mut values = [1, 2, 3];
+    let (mut first, rest) = assert_no_alloc(|| {
+        MuCow::Borrowed(&mut values[..]).into_split_first().unwrap()
+    });
+    assert!(first.is_borrowed() && rest.is_borrowed());
+    assert_eq!(rest, [2, 3]);
+    *first = 10;
+
+    let (mut last, rest) = assert_no_alloc(|| {
+        MuCow::Borrowed(&mut values[..]).into_split_last().unwrap()
+    });
+    assert_eq!(rest, [10, 2]);
+    *last = 30;
+    assert_eq!(values, [10, 2, 30]);
+
+    let (first, rest) = MuCow::<[i32]>::Owned(vec![1, 2]).into_split_first().unwrap();
+    assert!(first.is_owned());
+    assert_eq!((*first, &*rest), (1, &[2][..]));
+    let (last, rest) = MuCow::<[i32]>::Owned(vec![1, 2]).into_split_last().unwrap();
+    assert_eq!((*last, &*rest), (2, &[1][..]));
+
+    let mut empty: [i32; 0] = [];
+    assert!(MuCow::Borrowed(&mut empty[..]).into_split_first().is_none());
+    assert!(MuCow::Borrowed(&mut empty[..]).into_split_last().is_none());
+    assert!(MuCow::<[i32]>::Owned(Vec::new()).into_split_first().is_none());
+    assert!(MuCow::<[i32]>::Owned(Vec::new()).into_split_last().is_none());
+}
+
+#[test]
+fn get_disjoint_mut_checks_indices() {
+    let mut values = [1, 2, 3, 4];
+    let mut data = MuCow::Borrowed(&mut values[..]);
+    assert!(data.get_disjoint_mut([1, 1]).is_none());
+    assert!(data.get_disjoint_mut([0, 4]).is_none());
+    assert!(data.get_disjoint_mut([]).is_some());
+
+    {
+        let [mut last, mut first] = data.get_disjoint_mut([3, 0]).unwrap();
+        assert!(last.is_borrowed() && first.is_borrowed());
+        assert_eq!((*last, *first), (4, 1));
+        std::mem::swap(&mut *last, &mut *first);
+    }
+    assert!(data.is_borrowed());
+    assert_eq!(data, [4, 2, 3, 1]);
+    drop(data);
+    assert_eq!(values, [4, 2, 3, 1]);
+
+    let mut data: MuCow<[i32]> = MuCow::Owned(vec![1, 2]);
+    {
+        let [mut a, mut b] = data.get_disjoint_mut([0, 1]).unwrap();
+        *a += 10;
+        *b += 20;
+    }
+    assert_eq!(data, [11, 22]);
+}
+
+#[test]
+fn writes_through_as_mut_ptr_match_index_mut() {
+    let mut values = [1, 2, 3];
+    {
+        let mut data = MuCow::Borrowed(&mut values[..]);
+        let ptr = data.as_mut_ptr();
+        // SAFETY: index 1 is in bounds and nothing else accesses the data
+        // while the pointer is in use.
+        unsafe { *ptr.add(1) = 20 };
+        assert!(data.is_borrowed());
+        assert_eq!(data[1], 20);
+        data[2] = 30;
+        assert_eq!(data.as_ptr(), ptr as *const i32);
+    }
+    assert_eq!(values, [1, 20, 30]);
+
+    let mut data: MuCow<[i32]> = MuCow::Owned(vec![1, 2, 3]);
+    // SAFETY: index 0 is in bounds.
+    unsafe { *data.as_mut_ptr() = 10 };
+    assert_eq!(data, [10, 2, 3]);
+}
+
+#[test]
+fn get_and_get_mut() {
+    let mut values = [1, 2, 3];
+    {
+        let mut data = MuCow::Borrowed(&mut values[..]);
+        assert_eq!(data.get(0), Some(&1));
+        assert_eq!(data.get(3), None);
+        assert_eq!(data.get(1..3), Some(&[2, 3][..]));
+        assert_eq!(data.get(3..), Some(&[][..]));
+        assert_eq!(data.get(2..4), None);
+        assert_eq!(data.get(usize::MAX), None);
+
+        *data.get_mut(0).unwrap() = 10;
+        data.get_mut(1..).unwrap().copy_from_slice(&[20, 30]);
+        assert!(data.get_mut(3).is_none());
+        assert!(data.get_mut(1..4).is_none());
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(values, [10, 20, 30]);
+
+    let mut data: MuCow<[i32]> = MuCow::Owned(vec![1, 2]);
+    assert_eq!(data.get(..1), Some(&[1][..]));
+    *data.get_mut(1).unwrap() = 5;
+    assert_eq!(data, [1, 5]);
+    assert!(data.get_mut(2).is_none());
+}
+
+#[test]
+fn get_unchecked_in_bounds() {
+    let mut values = [1, 2, 3];
+    {
+        let mut data = MuCow::Borrowed(&mut values[..]);
+        // SAFETY: every index and range below is in bounds.
+        unsafe {
+            assert_eq!(*data.get_unchecked(2), 3);
+            assert_eq!(data.get_unchecked(..2), [1, 2]);
+            *data.get_unchecked_mut(0) = 10;
+            data.get_unchecked_mut(1..).copy_from_slice(&[20, 30]);
+        }
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(values, [10, 20, 30]);
+
+    let mut data: MuCow<[i32]> = MuCow::Owned(vec![1, 2]);
+    // SAFETY: index 1 is in bounds.
+    unsafe { *data.get_unchecked_mut(1) = 5 };
+    assert_eq!(data, [1, 5]);
+}
+
+#[test]
+fn advance_and_split_to_borrowed() {
+    let mut values = [1, 2, 3, 4];
+    {
+        let mut data = MuCow::Borrowed(&mut values[..]);
+        let mut head = assert_no_alloc(|| {
+            data.advance(1);
+            data.split_to(2)
+        });
+        assert!(head.is_borrowed() && data.is_borrowed());
+        assert_eq!(head, [2, 3]);
+        assert_eq!(data, [4]);
+        head[0] = 20;
+        data[0] = 40;
+        let rest = assert_no_alloc(|| data.split_to(1));
+        assert_eq!(rest, [40]);
+        assert!(data.is_empty() && data.is_borrowed());
+    }
+    assert_eq!(values, [1, 20, 3, 40]);
+}
+
+#[test]
+fn advance_and_split_to_owned() {
+    let mut data: MuCow<[i32]> = MuCow::Owned(vec![1, 2, 3, 4]);
+    data.advance(1);
+    assert_eq!(data, [2, 3, 4]);
+    let head = data.split_to(2);
+    assert!(head.is_owned() && data.is_owned());
+    assert_eq!(head, [2, 3]);
+    assert_eq!(data, [4]);
+    assert!(data.split_to(0).is_empty());
+    assert_eq!(data, [4]);
+}
+
+#[test]
+#[should_panic(expected = "range end index 3 out of range for slice of length 2")]
+fn split_to_past_the_end_panics() {
+    let mut values = [1, 2];
+    let _ = MuCow::Borrowed(&mut values[..]).split_to(3);
+}
+
+#[test]
+#[should_panic(expected = "range end index 3 out of range for slice of length 2")]
+fn advance_past_the_end_panics() {
+    let mut data: MuCow<[i32]> = MuCow::Owned(vec![1, 2]);
+    data.advance(3);
+}
+
+#[test]
+fn first_and_last() {
+    let mut values = [1, 2, 3];
+    {
+        let mut data = MuCow::Borrowed(&mut values[..]);
+        assert_eq!((data.first(), data.last()), (Some(&1), Some(&3)));
+        *data.first_mut().unwrap() = 10;
+        *data.last_mut().unwrap() = 30;
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(values, [10, 2, 30]);
+
+    let mut data: MuCow<[i32]> = MuCow::Owned(vec![5]);
+    *data.first_mut().unwrap() += 1;
+    assert_eq!((data.first(), data.last()), (Some(&6), Some(&6)));
+
+    let mut empty: [i32; 0] = [];
+    let mut data = MuCow::Borrowed(&mut empty[..]);
+    assert_eq!((data.first(), data.last()), (None, None));
+    assert!(data.first_mut().is_none() && data.last_mut().is_none());
+    let mut data: MuCow<[i32]> = MuCow::Owned(Vec::new());
+    assert!(data.first_mut().is_none() && data.last().is_none());
+}
+
+#[test]
+fn growing_edits_promote_with_exact_capacity() {
+    let mut values = [1, 2, 3];
+    {
+        let mut data = MuCow::Borrowed(&mut values[..]);
+        assert_no_alloc(|| {
+            data.extend_from_slice(&[]);
+            data.resize(3, 0);
+            data.resize_with(3, || 0);
+        });
+        assert!(data.is_borrowed());
+
+        data.extend_from_slice(&[4, 5]);
+        assert!(data.is_owned());
+        assert_eq!(data.as_owned().unwrap().capacity(), 5);
+        data.push(6);
+        data.insert(0, 0);
+        assert_eq!(data, [0, 1, 2, 3, 4, 5, 6]);
+    }
+    {
+        let mut data = MuCow::Borrowed(&mut values[..]);
+        data.resize(1, 0);
+        assert_eq!(data, [1]);
+        assert_eq!(data.as_owned().unwrap().capacity(), 1);
+    }
+    {
+        let mut data = MuCow::Borrowed(&mut values[..]);
+        data.push(4);
+        assert_eq!(data.as_owned().unwrap().capacity(), 4);
+        let mut n = 10;
+        data.resize_with(6, || {
+            n += 1;
+            n
+        });
+        assert_eq!(data, [1, 2, 3, 4, 11, 12]);
+    }
+    assert_eq!(values, [1, 2, 3]);
+}
+
+#[test]
+fn insert_out_of_bounds_panics_without_promoting() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut values = [1];
+    let mut data = MuCow::Borrowed(&mut values[..]);
+    assert!(catch_unwind(AssertUnwindSafe(|| data.insert(2, 0))).is_err());
+    assert!(data.is_borrowed());
+}
+
+#[test]
+#[should_panic(expected = "insertion index (is 2) should be <= len (is 1)")]
+fn insert_out_of_bounds_panics() {
+    let mut values = [1];
+    MuCow::Borrowed(&mut values[..]).insert(2, 0);
+}
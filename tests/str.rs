@@ -0,0 +1,410 @@
+extern crate mucow;
+
+use mucow::MuCow;
+
+mod common;
+use common::assert_no_alloc;
+
+#[test]
+fn push_leaves_the_original_alone() {
+    let mut buf = String::from("hello");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    data.push_str(", world");
+    data.push('!');
+    assert!(data.is_owned());
+    assert_eq!(data, "hello, world!");
+    drop(data);
+    assert_eq!(buf, "hello");
+}
+
+#[test]
+fn truncate_shortens_the_data() {
+    let mut buf = String::from("héllo");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    data.truncate(3);
+    assert_eq!(data, "hé");
+    drop(data);
+    assert_eq!(buf, "héllo");
+}
+
+#[test]
+#[should_panic]
+fn truncate_panics_off_a_char_boundary() {
+    let mut data: MuCow<str> = MuCow::Owned(String::from("héllo"));
+    data.truncate(2);
+}
+
+#[test]
+fn len_and_is_empty() {
+    let mut buf = String::from("héllo");
+    let data = MuCow::Borrowed(buf.as_mut_str());
+    assert_eq!(MuCow::<str>::len(&data), 6);
+    assert_eq!(data.len(), 6);
+    assert!(!data.is_empty());
+
+    let data: MuCow<str> = MuCow::Owned(String::new());
+    assert_eq!(MuCow::<str>::len(&data), 0);
+    assert!(MuCow::<str>::is_empty(&data));
+}
+
+#[test]
+fn into_lines_matches_str_lines() {
+    for text in ["", "a", "a\n", "a\n\nb", "a\r\nb\r\n", "\nb\n\n", "a\rb"] {
+        let expected: Vec<&str> = text.lines().collect();
+
+        let mut buf = String::from(text);
+        let lines: Vec<MuCow<str>> = MuCow::Borrowed(buf.as_mut_str()).into_lines().collect();
+        assert!(lines.iter().all(MuCow::is_borrowed));
+        assert_eq!(lines, expected, "{:?}", text);
+
+        let lines: Vec<MuCow<str>> = MuCow::<str>::Owned(String::from(text)).into_lines().collect();
+        assert!(lines.iter().all(MuCow::is_owned));
+        assert_eq!(lines, expected, "{:?}", text);
+    }
+}
+
+#[test]
+fn into_split_matches_str_split() {
+    for text in ["", ",", "a,b", ",a,,b,", "a,b,"] {
+        let expected: Vec<&str> = text.split(',').collect();
+
+        let mut buf = String::from(text);
+        let pieces: Vec<MuCow<str>> = MuCow::Borrowed(buf.as_mut_str()).into_split(',').collect();
+        assert!(pieces.iter().all(MuCow::is_borrowed));
+        assert_eq!(pieces, expected, "{:?}", text);
+
+        let pieces: Vec<MuCow<str>> = MuCow::<str>::Owned(String::from(text)).into_split(',').collect();
+        assert_eq!(pieces, expected, "{:?}", text);
+    }
+}
+
+#[test]
+fn borrowed_lines_and_pieces_write_through() {
+    let mut buf = String::from("one\r\ntwo\n\nthree\n");
+    for mut line in MuCow::Borrowed(buf.as_mut_str()).into_lines() {
+        line.make_ascii_uppercase();
+    }
+    assert_eq!(buf, "ONE\r\nTWO\n\nTHREE\n");
+
+    let mut buf = String::from("a;bc;;d");
+    for mut piece in MuCow::Borrowed(buf.as_mut_str()).into_split(';') {
+        piece.make_ascii_uppercase();
+    }
+    assert_eq!(buf, "A;BC;;D");
+}
+
+#[test]
+fn into_split_at_the_ends() {
+    let mut buf = String::from("héllo");
+    let (left, right) = assert_no_alloc(|| MuCow::Borrowed(buf.as_mut_str()).into_split_at(0));
+    assert!(left.is_borrowed() && right.is_borrowed());
+    assert_eq!((&*left, &*right), ("", "héllo"));
+
+    let (left, right) = assert_no_alloc(|| MuCow::Borrowed(buf.as_mut_str()).into_split_at(6));
+    assert_eq!((&*left, &*right), ("héllo", ""));
+
+    let (mut left, mut right) = MuCow::Borrowed(buf.as_mut_str()).into_split_at(3);
+    left.make_ascii_uppercase();
+    right.make_ascii_uppercase();
+    assert_eq!(buf, "HéLLO");
+
+    let (left, right) = MuCow::<str>::Owned(String::from("héllo")).into_split_at(3);
+    assert!(left.is_owned() && right.is_owned());
+    assert_eq!((&*left, &*right), ("hé", "llo"));
+}
+
+#[test]
+#[should_panic]
+fn into_split_at_past_the_end_panics() {
+    let mut buf = String::from("hi");
+    let _ = MuCow::Borrowed(buf.as_mut_str()).into_split_at(3);
+}
+
+#[test]
+fn no_op_edits_stay_borrowed() {
+    let mut buf = String::from("héllo");
+    {
+        let mut data = MuCow::Borrowed(buf.as_mut_str());
+        assert_no_alloc(|| {
+            data.push_str("");
+            data.insert_str(1, "");
+            data.truncate(6);
+            data.truncate(100);
+        });
+        assert!(data.is_borrowed());
+        data.replace_range(1..3, "è");
+        data.replace_range(..1, "J");
+        assert!(data.is_borrowed());
+        assert_eq!(data, "Jèllo");
+    }
+    assert_eq!(buf, "Jèllo");
+
+    let mut empty = String::new();
+    let mut data = MuCow::Borrowed(empty.as_mut_str());
+    assert_eq!(data.pop(), None);
+    assert!(data.is_borrowed());
+}
+
+#[test]
+fn other_edits_promote() {
+    let edits: [fn(&mut MuCow<str>); 7] = [
+        |data| data.push_str("!"),
+        |data| data.push('!'),
+        |data| data.insert(0, '!'),
+        |data| data.insert_str(0, "!"),
+        |data| data.truncate(3),
+        |data| assert_eq!(data.pop(), Some('o')),
+        |data| assert_eq!(data.remove(1), 'é'),
+    ];
+    for edit in &edits {
+        let mut buf = String::from("héllo");
+        {
+            let mut data = MuCow::Borrowed(buf.as_mut_str());
+            edit(&mut data);
+            assert!(data.is_owned());
+        }
+        assert_eq!(buf, "héllo");
+    }
+
+    let mut buf = String::from("héllo");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    data.replace_range(1..3, "e");
+    assert!(data.is_owned());
+    assert_eq!(data, "hello");
+}
+
+#[test]
+fn invalid_edits_panic_without_promoting() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let edits: [fn(&mut MuCow<str>); 8] = [
+        |data| data.truncate(2),
+        |data| data.insert(2, '!'),
+        |data| data.insert(7, '!'),
+        |data| {
+            data.remove(2);
+        },
+        |data| {
+            data.remove(6);
+        },
+        |data| data.insert_str(2, "!"),
+        |data| data.replace_range(2..3, "!"),
+        |data| data.replace_range(0..7, "!"),
+    ];
+    for edit in &edits {
+        let mut buf = String::from("héllo");
+        let mut data = MuCow::Borrowed(buf.as_mut_str());
+        assert!(catch_unwind(AssertUnwindSafe(|| edit(&mut data))).is_err());
+        assert!(data.is_borrowed());
+    }
+}
+
+#[test]
+#[should_panic(expected = "byte index 2 is not a char boundary")]
+fn insert_panics_off_a_char_boundary_while_borrowed() {
+    use std::panic::{catch_unwind, resume_unwind, AssertUnwindSafe};
+
+    let mut buf = String::from("héllo");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    let result = catch_unwind(AssertUnwindSafe(|| data.insert(2, '!')));
+    assert!(data.is_borrowed());
+    if let Err(payload) = result {
+        resume_unwind(payload);
+    }
+}
+
+#[test]
+#[should_panic(expected = "byte index 2 is not a char boundary")]
+fn remove_panics_off_a_char_boundary() {
+    let mut buf = String::from("héllo");
+    MuCow::Borrowed(buf.as_mut_str()).remove(2);
+}
+
+#[test]
+fn case_conversion_in_place() {
+    let mut buf = String::from("Hello, World");
+    {
+        let mut data = MuCow::Borrowed(buf.as_mut_str());
+        assert_no_alloc(|| data.to_uppercase_in_place());
+        assert!(data.is_borrowed());
+        assert_eq!(data, "HELLO, WORLD");
+        data.to_lowercase_in_place();
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(buf, "hello, world");
+
+    let mut buf = String::from("café");
+    MuCow::Borrowed(buf.as_mut_str()).to_uppercase_in_place();
+    assert_eq!(buf, "CAFÉ");
+
+    let mut empty = String::new();
+    let mut data = MuCow::Borrowed(empty.as_mut_str());
+    assert_no_alloc(|| {
+        data.to_uppercase_in_place();
+        data.to_lowercase_in_place();
+    });
+    assert!(data.is_borrowed() && data.is_empty());
+}
+
+#[test]
+fn case_conversion_that_changes_length_promotes() {
+    let cases = [
+        ("straße", true, "STRASSE"),
+        ("İ", false, "i\u{307}"),
+        ("ΣΑΣ", false, "σας"),
+    ];
+    for &(input, upper, expected) in &cases {
+        let mut buf = String::from(input);
+        {
+            let mut data = MuCow::Borrowed(buf.as_mut_str());
+            if upper {
+                data.to_uppercase_in_place();
+            } else {
+                data.to_lowercase_in_place();
+            }
+            assert!(data.is_owned(), "{:?}", input);
+            assert_eq!(data, expected);
+        }
+        assert_eq!(buf, input);
+    }
+}
+
+#[test]
+fn writes_through_as_mut_ptr_match_index_mut() {
+    let mut buf = String::from("hello");
+    {
+        let mut data = MuCow::Borrowed(buf.as_mut_str());
+        let ptr = data.as_mut_ptr();
+        // SAFETY: index 0 is in bounds, and the ASCII byte keeps the data
+        // valid UTF-8.
+        unsafe { *ptr = b'j' };
+        assert!(data.is_borrowed());
+        assert_eq!(data, "jello");
+        data[1..].make_ascii_uppercase();
+        assert_eq!(data.as_ptr(), ptr as *const u8);
+    }
+    assert_eq!(buf, "jELLO");
+}
+
+#[test]
+fn lazy_transforms_keep_clean_input_borrowed() {
+    let mut buf = String::from("  hello  ");
+    let data = assert_no_alloc(|| MuCow::Borrowed(buf.as_mut_str()).trimmed());
+    assert!(data.is_borrowed());
+    assert_eq!(data, "hello");
+    let data = assert_no_alloc(|| data.to_lowercase_lazy());
+    assert!(data.is_borrowed());
+    let data = assert_no_alloc(|| data.map_if(|s| s.contains(' '), |s| s.replace(' ', "_")));
+    assert!(data.is_borrowed());
+    assert_eq!(data, "hello");
+
+    let data: MuCow<str> = MuCow::Owned(String::from("\thi\n"));
+    let data = data.trimmed();
+    assert!(data.is_owned());
+    assert_eq!(data, "hi");
+}
+
+#[test]
+fn lazy_transforms_convert_dirty_input() {
+    let mut buf = String::from("Hello World");
+    let data = MuCow::Borrowed(buf.as_mut_str()).to_lowercase_lazy();
+    assert!(data.is_owned());
+    assert_eq!(data, "hello world");
+    let data = data.map_if(|s| s.contains(' '), |s| s.replace(' ', "_"));
+    assert_eq!(data, "hello_world");
+
+    let mut buf = String::from("ΣΑΣ");
+    let data = MuCow::Borrowed(buf.as_mut_str()).to_lowercase_lazy();
+    assert_eq!(data, "σας");
+    assert_eq!(buf, "ΣΑΣ");
+}
+
+#[test]
+fn equal_length_replacement_writes_through() {
+    let mut buf = String::from("one two one");
+    {
+        let mut data = MuCow::Borrowed(buf.as_mut_str());
+        assert!(!data.would_allocate("one", "uno"));
+        assert_no_alloc(|| data.replace_str("one", "uno"));
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(buf, "uno two uno");
+
+    let mut buf = String::from("aaaaa");
+    MuCow::Borrowed(buf.as_mut_str()).replace_str("aa", "bb");
+    assert_eq!(buf, "aaaaa".replace("aa", "bb"));
+    assert_eq!(buf, "bbbba");
+
+    let mut buf = String::from("aaaa");
+    MuCow::Borrowed(buf.as_mut_str()).replacen_str("aa", "ab", 1);
+    assert_eq!(buf, "abaa");
+
+    let mut buf = String::from("café é");
+    {
+        let mut data = MuCow::Borrowed(buf.as_mut_str());
+        assert!(!data.would_allocate("é", "ab"));
+        data.replace_str("é", "ab");
+        assert!(data.is_borrowed());
+    }
+    assert_eq!(buf, "cafab ab");
+}
+
+#[test]
+fn different_length_replacement_promotes() {
+    let mut buf = String::from("one two one");
+    {
+        let mut data = MuCow::Borrowed(buf.as_mut_str());
+        assert!(data.would_allocate("one", "1"));
+        data.replacen_str("one", "1", 1);
+        assert!(data.is_owned());
+        assert_eq!(data, "1 two one");
+    }
+    assert_eq!(buf, "one two one");
+}
+
+#[test]
+fn replacement_without_matches_stays_borrowed() {
+    let mut buf = String::from("hello");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    assert!(!data.would_allocate("xyz", "a"));
+    assert_no_alloc(|| {
+        data.replace_str("xyz", "a");
+        data.replace_str("xy", "ab");
+        data.replacen_str("l", "", 0);
+    });
+    assert!(data.is_borrowed());
+    assert_eq!(data, "hello");
+}
+
+#[test]
+fn parsing_by_narrowing_stays_borrowed() {
+    const TOKENS: [&str; 5] = ["let", "x", "=", "1", ";"];
+
+    let mut buf = String::from("  let x = 1; let x=1 ;\n");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    let mut count = 0;
+    assert_no_alloc(|| {
+        data.trim_end_in_place();
+        loop {
+            data.trim_start_in_place();
+            if data.is_empty() {
+                break;
+            }
+            let token = TOKENS.iter().find(|t| data.starts_with(*t)).unwrap();
+            assert!(data.consume_prefix(token));
+            assert!(data.is_borrowed());
+            count += 1;
+        }
+    });
+    assert_eq!(count, 10);
+    assert!(data.is_borrowed());
+    assert!(data.is_empty());
+
+    let mut buf = String::from("[abc]");
+    let mut data = MuCow::Borrowed(buf.as_mut_str());
+    assert!(data.consume_prefix("[") && data.consume_suffix("]"));
+    assert!(!data.consume_prefix("x"));
+    assert!(data.consume_prefix("abc"));
+    assert!(data.is_borrowed() && data.is_empty());
+    assert_eq!(data.as_ptr(), buf[4..].as_ptr());
+}
@@ -0,0 +1,142 @@
+extern crate mucow;
+
+use mucow::MuCow;
+
+#[test]
+fn push_on_borrowed_leaves_the_original_unchanged() {
+    let mut v = vec![1, 2, 3];
+    let mut data = MuCow::Borrowed(&mut v);
+    data.push(4);
+    assert!(data.is_owned());
+    assert_eq!(*data, [1, 2, 3, 4]);
+    assert_eq!(data.pop(), Some(4));
+    drop(data);
+    assert_eq!(v, [1, 2, 3]);
+}
+
+#[test]
+fn extend_clones_borrowed_data_once() {
+    use std::rc::Rc;
+
+    let mut v = vec![Rc::new(1), Rc::new(2)];
+    {
+        let mut data = MuCow::Borrowed(&mut v);
+        data.extend(vec![Rc::new(3)]);
+        assert!(data.is_owned());
+        data.extend(vec![Rc::new(4), Rc::new(5)]);
+        data.extend(Vec::new());
+        let values: Vec<i32> = data.iter().map(|x| **x).collect();
+        assert_eq!(values, [1, 2, 3, 4, 5]);
+        // Each original element was cloned exactly once, by the first call.
+        assert_eq!(Rc::strong_count(&data[0]), 2);
+        assert_eq!(Rc::strong_count(&data[1]), 2);
+        assert_eq!(Rc::strong_count(&data[2]), 1);
+    }
+    assert_eq!(v.len(), 2);
+    assert_eq!(Rc::strong_count(&v[0]), 1);
+}
+
+#[test]
+fn retain_clones_only_the_retained_elements() {
+    use std::rc::Rc;
+
+    let mut v: Vec<Rc<i32>> = (1..=5).map(Rc::new).collect();
+    {
+        let mut data = MuCow::Borrowed(&mut v);
+        data.retain(|x| **x % 2 == 1);
+        assert!(data.is_owned());
+        let values: Vec<i32> = data.iter().map(|x| **x).collect();
+        assert_eq!(values, [1, 3, 5]);
+        for x in data.iter() {
+            assert_eq!(Rc::strong_count(x), 2);
+        }
+        assert_eq!(Rc::strong_count(&v[1]), 1);
+        assert_eq!(Rc::strong_count(&v[3]), 1);
+    }
+    let values: Vec<i32> = v.iter().map(|x| **x).collect();
+    assert_eq!(values, [1, 2, 3, 4, 5]);
+    assert!(v.iter().all(|x| Rc::strong_count(x) == 1));
+
+    let mut data: MuCow<Vec<i32>> = MuCow::Owned(vec![1, 2, 3, 4]);
+    data.retain(|&x| x > 2);
+    assert_eq!(*data, [3, 4]);
+}
+
+#[test]
+fn drain_returns_the_removed_elements() {
+    let mut v = vec![1, 2, 3, 4, 5];
+    {
+        let mut data = MuCow::Borrowed(&mut v);
+        let drained: Vec<i32> = data.drain(1..3).collect();
+        assert_eq!(drained, [2, 3]);
+        assert!(data.is_owned());
+        assert_eq!(*data, [1, 4, 5]);
+        let drained: Vec<i32> = data.drain(..).collect();
+        assert_eq!(drained, [1, 4, 5]);
+        assert!(data.is_empty());
+    }
+    assert_eq!(v, [1, 2, 3, 4, 5]);
+
+    let mut data: MuCow<Vec<i32>> = MuCow::Owned(vec![1, 2, 3]);
+    let drained: Vec<i32> = data.drain(2..).collect();
+    assert_eq!(drained, [3]);
+    assert_eq!(*data, [1, 2]);
+    assert_eq!(data.drain(1..1).count(), 0);
+    assert_eq!(*data, [1, 2]);
+}
+
+#[test]
+#[should_panic]
+fn drain_out_of_bounds_panics() {
+    let mut data: MuCow<Vec<i32>> = MuCow::Owned(vec![1, 2]);
+    data.drain(1..3);
+}
+
+#[test]
+fn split_off_halves_reconstruct_the_original() {
+    for at in 0..=4 {
+        let mut v = vec![1, 2, 3, 4];
+        {
+            let mut data = MuCow::Borrowed(&mut v);
+            let tail = data.split_off(at);
+            assert!(data.is_owned() && tail.is_owned());
+            assert_eq!(data.len(), at);
+            let mut joined = data.into_owned();
+            joined.extend_from_slice(&tail);
+            assert_eq!(joined, [1, 2, 3, 4]);
+        }
+        assert_eq!(v, [1, 2, 3, 4]);
+    }
+
+    let mut data: MuCow<Vec<i32>> = MuCow::Owned(vec![1, 2, 3]);
+    let tail = data.split_off(1);
+    assert_eq!(*data, [1]);
+    assert_eq!(*tail, [2, 3]);
+}
+
+#[test]
+#[should_panic]
+fn split_off_past_the_end_panics() {
+    let mut data: MuCow<Vec<i32>> = MuCow::Owned(vec![1, 2]);
+    let _ = data.split_off(3);
+}
+
+#[test]
+fn append_moves_every_element() {
+    let mut v = vec![1, 2];
+    {
+        let mut data = MuCow::Borrowed(&mut v);
+        let mut other = vec![3, 4, 5];
+        data.append(&mut other);
+        assert!(other.is_empty());
+        assert!(data.is_owned());
+        assert_eq!(*data, [1, 2, 3, 4, 5]);
+    }
+    assert_eq!(v, [1, 2]);
+
+    let mut data: MuCow<Vec<i32>> = MuCow::Owned(Vec::new());
+    let mut other = vec![1, 2];
+    data.append(&mut other);
+    assert!(other.is_empty());
+    assert_eq!(*data, [1, 2]);
+}